@@ -1,7 +1,8 @@
+use proc_macro2::{Group, TokenTree};
 use quote::ToTokens;
 use syn::{spanned::Spanned, PatType};
 
-use self::parsing::PathParam;
+use self::parsing::{PathParam, RouteOptions};
 
 use super::*;
 
@@ -11,6 +12,9 @@ pub struct CompiledRoute {
     pub query_params: Vec<(Ident, Box<Type>)>,
     pub state: Type,
     pub route_lit: LitStr,
+    pub options: RouteOptions,
+    /// The path of the `axum-routing-htmx` crate.
+    pub krate: syn::Path,
 }
 
 impl CompiledRoute {
@@ -20,10 +24,20 @@ impl CompiledRoute {
         for (_slash, param) in &self.path_params {
             path.push('/');
             match param {
+                PathParam::Capture(lit, _, _, _) if cfg!(feature = "axum-08") => {
+                    path.push('{');
+                    path.push_str(&lit.value());
+                    path.push('}');
+                }
                 PathParam::Capture(lit, _, _, _) => {
                     path.push(':');
                     path.push_str(&lit.value())
                 }
+                PathParam::WildCard(lit, _, _, _) if cfg!(feature = "axum-08") => {
+                    path.push_str("{*");
+                    path.push_str(&lit.value());
+                    path.push('}');
+                }
                 PathParam::WildCard(lit, _, _, _) => {
                     path.push('*');
                     path.push_str(&lit.value());
@@ -35,38 +49,33 @@ impl CompiledRoute {
         path
     }
 
-    pub fn to_format_path_string(&self) -> String {
-        let mut path = String::new();
+    /// The full path of the route, if it doesn't have any path- or query-parameters.
+    pub fn to_static_path_string(&self) -> Option<String> {
+        if !self.query_params.is_empty() {
+            return None;
+        }
 
+        let mut path = String::new();
         for (_slash, param) in &self.path_params {
             path.push('/');
             match param {
-                PathParam::Capture(_, _, _, _) => {
-                    path.push_str("{}");
-                }
-                PathParam::WildCard(_, _, _, _) => {
-                    path.push_str("{}");
-                }
                 PathParam::Static(lit) => path.push_str(&lit.value()),
+                _ => return None,
             }
         }
-
-        for (i, param) in self.query_params.iter().enumerate() {
-            if i == 0 {
-                path.push('?');
-            } else {
-                path.push('&');
-            }
-            path.push_str(&param.0.to_string());
-            path.push_str("={}");
-        }
-
-        path
+        Some(path)
     }
 
     /// Removes the arguments in `route` from `args`, and merges them in the output.
     pub fn from_route(mut route: Route, function: &ItemFn) -> syn::Result<Self> {
         let sig = &function.sig;
+        if let Some(receiver) = sig.receiver() {
+            return Err(syn::Error::new_spanned(
+                receiver,
+                "handlers can't take `self`, use an associated function without a receiver instead",
+            ));
+        }
+
         let mut arg_map = sig
             .inputs
             .iter()
@@ -122,6 +131,12 @@ impl CompiledRoute {
 
         Ok(Self {
             route_lit: route.route_lit,
+            krate: route
+                .options
+                .krate
+                .clone()
+                .unwrap_or_else(|| parse_quote!(::axum_routing_htmx)),
+            options: route.options,
             path_params: route.path_params,
             query_params,
             state: route.state.unwrap_or_else(|| guess_state_type(sig)),
@@ -129,6 +144,7 @@ impl CompiledRoute {
     }
 
     pub fn path_extractor(&self) -> Option<TokenStream2> {
+        let krate = &self.krate;
         if !self.path_params.iter().any(|(_, param)| param.captures()) {
             return None;
         }
@@ -140,32 +156,35 @@ impl CompiledRoute {
         let idents = path_iter.clone().map(|item| item.0);
         let types = path_iter.clone().map(|item| item.1);
         Some(quote! {
-            ::axum::extract::Path((#(#idents,)*)): ::axum::extract::Path<(#(#types,)*)>,
+            #krate::__private::axum::extract::Path((#(#idents,)*)): #krate::__private::axum::extract::Path<(#(#types,)*)>,
         })
     }
 
     pub fn query_extractor(&self) -> Option<TokenStream2> {
+        let krate = &self.krate;
         if self.query_params.is_empty() {
             return None;
         }
 
         let idents = self.query_params.iter().map(|item| &item.0);
         Some(quote! {
-            ::axum::extract::Query(__QueryParams__ {
+            #krate::__private::axum::extract::Query(__QueryParams__ {
                 #(#idents,)*
-            }): ::axum::extract::Query<__QueryParams__>,
+            }): #krate::__private::axum::extract::Query<__QueryParams__>,
         })
     }
 
     pub fn query_params_struct(&self) -> Option<TokenStream2> {
+        let krate = &self.krate;
         match self.query_params.is_empty() {
             true => None,
             false => {
                 let idents = self.query_params.iter().map(|item| &item.0);
                 let types = self.query_params.iter().map(|item| &item.1);
-                let derive = quote! { #[derive(::serde::Deserialize)] };
+                let serde_crate = format!("{}::__private::serde", krate.to_token_stream());
                 Some(quote! {
-                    #derive
+                    #[derive(#krate::__private::serde::Deserialize)]
+                    #[serde(crate = #serde_crate)]
                     struct __QueryParams__ {
                         #(#idents: #types,)*
                     }
@@ -174,6 +193,90 @@ impl CompiledRoute {
         }
     }
 
+    /// The arguments of `htmx_path`, typed after the handler's parameters.
+    /// `String` parameters accept any `impl AsRef<str>`, `Option<String>` parameters an
+    /// `Option<&str>`, and all others any `impl Borrow<T>`, so values can be passed both
+    /// owned and by reference. The `&str` is given the `lifetime`, if any.
+    pub fn htmx_path_args(&self, lifetime: Option<TokenStream2>) -> TokenStream2 {
+        let args = self
+            .path_params
+            .iter()
+            .filter_map(|(_slash, path_param)| path_param.capture())
+            .chain(self.query_params.iter().map(|(ident, ty)| (ident, &**ty)))
+            .map(|(ident, ty)| {
+                if is_string(ty) {
+                    quote! { #ident: impl ::std::convert::AsRef<str> }
+                } else if is_option_string(ty) {
+                    quote! { #ident: ::std::option::Option<&#lifetime str> }
+                } else {
+                    quote! { #ident: impl ::std::borrow::Borrow<#ty> }
+                }
+            });
+        quote! { #(#args,)* }
+    }
+
+    /// Writes the path to the writer `__w`, serializing the path- and query-parameters
+    /// with serde, like the `Path` and `Query` extractors expect.
+    pub fn htmx_path_writer(&self) -> TokenStream2 {
+        let krate = &self.krate;
+        let path_writer = self
+            .path_params
+            .iter()
+            .map(|(_slash, path_param)| match path_param {
+                PathParam::Capture(_, _, ident, ty) => {
+                    let key = ident.to_string();
+                    let value = borrowed_arg(ident, ty);
+                    // Spanned to the type, for the error if it isn't `Serialize`.
+                    let write = respan(
+                        quote! { #krate::url::write_segment(&mut *__w, #key, #value) },
+                        ty.span(),
+                    );
+                    quote! {
+                        __w.write_char('/')?;
+                        #write?;
+                    }
+                }
+                PathParam::WildCard(_, _, ident, ty) => {
+                    let key = ident.to_string();
+                    let value = borrowed_arg(ident, ty);
+                    // Spanned to the type, for the error if it isn't `Serialize`.
+                    let write = respan(
+                        quote! { #krate::url::write_wildcard(&mut *__w, #key, #value) },
+                        ty.span(),
+                    );
+                    quote! {
+                        __w.write_char('/')?;
+                        #write?;
+                    }
+                }
+                PathParam::Static(lit) => {
+                    let segment = format!("/{}", lit.value());
+                    quote! { __w.write_str(#segment)?; }
+                }
+            });
+
+        let query_writer = (!self.query_params.is_empty()).then(|| {
+            let keys = self
+                .query_params
+                .iter()
+                .map(|(ident, _ty)| ident.to_string());
+            let values = self
+                .query_params
+                .iter()
+                .map(|(ident, ty)| borrowed_arg(ident, ty));
+            quote! {
+                let mut __query = #krate::url::QueryWriter::new(&mut *__w);
+                #(__query.push(#keys, #values)?;)*
+            }
+        });
+
+        quote! {
+            #(#path_writer)*
+            #query_writer
+            ::std::result::Result::Ok(())
+        }
+    }
+
     pub fn extracted_idents(&self) -> Vec<Ident> {
         let mut idents = Vec::new();
         for (_slash, path_param) in &self.path_params {
@@ -221,12 +324,105 @@ impl CompiledRoute {
                     new_pat_type.pat = Box::new(parse_quote!(#ident));
                     Some(new_pat_type)
                 } else {
-                    unimplemented!("Self type is not supported")
+                    // Receivers are rejected in `from_route`.
+                    None
                 }
             })
             .collect()
     }
 
+    /// The HTMX request headers that the response of the handler depends on, for its
+    /// `Vary` header. They are guessed from the extractors in its signature, by the name of
+    /// their type only: wrapped or renamed extractors aren't recognized.
+    pub fn vary_headers(&self, sig: &syn::Signature) -> Vec<&'static str> {
+        let mut headers = Vec::new();
+        if self.options.htmx_only.is_some() {
+            headers.push("HX-Request");
+        }
+        for arg in &sig.inputs {
+            let FnArg::Typed(pat_type) = arg else {
+                continue;
+            };
+            let Type::Path(ty) = &*pat_type.ty else {
+                continue;
+            };
+            let Some(segment) = ty.path.segments.last() else {
+                continue;
+            };
+            headers.extend_from_slice(match segment.ident.to_string().as_str() {
+                "HtmxRequest" => &[
+                    "HX-Request",
+                    "HX-Boosted",
+                    "HX-Current-URL",
+                    "HX-History-Restore-Request",
+                    "HX-Prompt",
+                    "HX-Target",
+                    "HX-Trigger",
+                    "HX-Trigger-Name",
+                ],
+                "HxRequest" => &["HX-Request"],
+                "HxBoosted" => &["HX-Boosted"],
+                "HxCurrentUrl" => &["HX-Current-URL"],
+                "HxHistoryRestoreRequest" => &["HX-History-Restore-Request"],
+                "HxPrompt" => &["HX-Prompt"],
+                "HxTarget" => &["HX-Target"],
+                "HxTrigger" => &["HX-Trigger"],
+                "HxTriggerName" => &["HX-Trigger-Name"],
+                _ => &[],
+            });
+        }
+        let mut seen = Vec::new();
+        headers.retain(|header| {
+            let new = !seen.contains(header);
+            seen.push(*header);
+            new
+        });
+        headers
+    }
+
+    /// A `RouteInfo` expression describing the route.
+    pub(crate) fn to_route_info(
+        &self,
+        enum_method: &Ident,
+        sig: &syn::Signature,
+        docs: &str,
+    ) -> TokenStream2 {
+        let krate = &self.krate;
+        let vary = self.vary_headers(sig);
+        let cache_control = optional_lit(&self.options.cache_control);
+        let axum_path = self.to_axum_path_string();
+        let route = self.route_lit.value();
+        let param_info = |ident: &Ident, ty: &Type| {
+            let name = ident.to_string();
+            let ty = type_name(ty);
+            quote! { #krate::info::ParamInfo { name: #name, ty: #ty } }
+        };
+        let path_params = self
+            .path_params
+            .iter()
+            .filter_map(|(_slash, path_param)| path_param.capture())
+            .map(|(ident, ty)| param_info(ident, ty));
+        let query_params = self
+            .query_params
+            .iter()
+            .map(|(ident, ty)| param_info(ident, ty));
+        let state_type = type_name(&self.state);
+
+        quote! {
+            #krate::info::RouteInfo {
+                method: #krate::HtmxMethod::#enum_method,
+                path: #axum_path,
+                route: #route,
+                path_params: &[#(#path_params,)*],
+                query_params: &[#(#query_params,)*],
+                state_type: #state_type,
+                docs: #docs,
+                vary: &[#(#vary,)*],
+                cache_control: #cache_control,
+            }
+        }
+    }
+
     pub(crate) fn to_doc_comments(&self) -> TokenStream2 {
         let doc = format!(
             "# Handler information
@@ -242,6 +438,87 @@ impl CompiledRoute {
     }
 }
 
+/// The name of a type as written in the source, without the spaces between its tokens.
+pub(crate) fn type_name(ty: &Type) -> String {
+    let mut name = ty.to_token_stream().to_string();
+    for (from, to) in [
+        (" :: ", "::"),
+        (":: ", "::"),
+        (" <", "<"),
+        ("< ", "<"),
+        (" >", ">"),
+        (" ,", ","),
+        ("& ", "&"),
+        ("( ", "("),
+        (" )", ")"),
+    ] {
+        name = name.replace(from, to);
+    }
+    name
+}
+
+/// Whether the type is syntactically `String`.
+fn is_string(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ty) => {
+            ty.qself.is_none()
+                && ty
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "String" && segment.arguments.is_none())
+        }
+        _ => false,
+    }
+}
+
+/// Whether the type is syntactically `Option<String>`.
+fn is_option_string(ty: &Type) -> bool {
+    let Type::Path(ty) = ty else {
+        return false;
+    };
+    let Some(segment) = ty.path.segments.last() else {
+        return false;
+    };
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return false;
+    };
+    ty.qself.is_none()
+        && segment.ident == "Option"
+        && args.args.len() == 1
+        && matches!(args.args.first(), Some(GenericArgument::Type(ty)) if is_string(ty))
+}
+
+/// Borrows an argument of `htmx_path` as the type of the handler's parameter.
+fn borrowed_arg(ident: &Ident, ty: &Type) -> TokenStream2 {
+    if is_string(ty) {
+        quote! { ::std::convert::AsRef::<str>::as_ref(&#ident) }
+    } else if is_option_string(ty) {
+        quote! { &#ident }
+    } else {
+        quote! { ::std::borrow::Borrow::<#ty>::borrow(&#ident) }
+    }
+}
+
+/// Sets the span of every token, so that errors in generated code point at the user's code.
+fn respan(tokens: impl ToTokens, span: Span) -> TokenStream2 {
+    tokens
+        .into_token_stream()
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Group(group) => {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                TokenTree::Group(respanned)
+            }
+            mut token => {
+                token.set_span(span);
+                token
+            }
+        })
+        .collect()
+}
+
 fn guess_state_type(sig: &syn::Signature) -> Type {
     for arg in &sig.inputs {
         if let FnArg::Typed(pat_type) = arg {
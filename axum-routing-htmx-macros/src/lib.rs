@@ -2,12 +2,14 @@ use compilation::CompiledRoute;
 use parsing::Route;
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::ToTokens;
 use std::collections::HashMap;
 use syn::{
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
     token::{Colon, Comma, Slash},
-    FnArg, GenericArgument, ItemFn, LitStr, PathArguments, Type,
+    Attribute, Expr, ExprLit, FnArg, GenericArgument, GenericParam, ImplItem, ItemFn, ItemImpl,
+    Lit, LitStr, Meta, MetaNameValue, PathArguments, Type,
 };
 #[macro_use]
 extern crate quote;
@@ -24,12 +26,29 @@ A macro that generates HTMX-compatible statically-typed ", $enum_verb, " routes
 
 # Syntax
 ```ignore
-#[", stringify!($method), "(\"<PATH>\" [with <STATE>])]
+#[", stringify!($method), "(\"<PATH>\" [with <STATE>] [, <OPTION> = <VALUE>]* [, htmx_only] [, debug])]
 ```
 - `PATH` is the path of the route, with optional path parameters and query parameters,
-    e.g. `/item/:id?amount&offset`.
+    e.g. `/item/:id?amount&offset`. Path parameters can be written in the syntax of either
+    axum 0.7 (`:id`, `*rest`) or axum 0.8 (`{id}`, `{*rest}`), and are registered in the
+    syntax of the axum version selected by the crate features.
 - `STATE` is the type of axum-state, passed to the handler. This is optional, and if not
     specified, the state type is guessed based on the parameters of the handler.
+- `OPTION`s are optional defaults for the HTML attributes rendered by `htmx_attrs`:
+    - `target = \"<SELECTOR>\"` for `hx-target`, e.g. `target = \"#todo-list\"`.
+    - `swap = \"<SWAP>\"` for `hx-swap`, e.g. `swap = \"outerHTML\"`.
+    - `trigger = \"<TRIGGER>\"` for `hx-trigger`, e.g. `trigger = \"click\"`.
+- `cache_control = \"<PRESET>\"` sets the `Cache-Control` header of responses that don't set it
+    themselves, to one of: `no-store`, `no-cache`, `private`, `public`.
+- The `htmx_only` flag rejects requests without the `HX-Request: true` header with
+    `404 Not Found`, e.g. when the URL of a fragment is opened directly. With
+    `htmx_only = \"<PATH>\"`, they are redirected to `PATH` instead.
+- The `debug` flag applies axum's `debug_handler` to the generated handler, so that errors
+    about arguments that aren't extractors, or responses that don't implement `IntoResponse`,
+    point at the handler. It is not supported on generic handlers, and requires a direct
+    dependency on axum.
+- `crate = <PATH>` sets the path of the `axum_routing_htmx` crate used by the generated code,
+    for crates that re-export it, e.g. `crate = ::my_web::htmx`.
 
 # Example
 ```
@@ -57,8 +76,51 @@ explicitly using the `with` keyword:
 #[", stringify!($method), "(\"/item/:id?amount&offset\" with String)]
 ```
 
+# Generated paths
+The generated struct has an `htmx_path` method, taking the path- and query-parameters in order.
+They are typed after the parameters of the handler, where `String` parameters accept any
+`impl AsRef<str>`, and `Option<String>` parameters an `Option<&str>` (use `as_deref` to pass
+an owned value). The parameters are serialized with serde, mirroring the `Path` and `Query`
+extractors of the handler: path parameters are percent-encoded as a segment, and optional
+query parameters that are `None` are left out of the path.
+
+The `htmx_url` method takes the same arguments, but returns a value that writes the path when
+it is displayed, so it can be rendered straight into a template without allocating a `String`.
+Displaying it fails in the cases where `htmx_path` panics.
+
+Routes without any path- or query-parameters also have a `PATH` constant, next to the
+`AXUM_PATH` and `METHOD` constants of all routes.
+
+# Original handler
+The handler function itself stays callable with its original signature, as the associated
+`handler` function of the generated struct `__HtmxHandler_<NAME>`. This allows testing it
+without a router:
+```ignore
+let response = __HtmxHandler_item_handler::handler(1, None, None, State(state), Json(2)).await;
+```
+
+# Vary headers
+When the handler extracts HTMX request headers, like `HxRequest` or `HtmxRequest`, its responses
+depend on them. The route then adds them to the `Vary` header of its responses, so that caches
+don't serve a fragment as the full page. `HtmxRequest` varies on all the headers it reads.
+
+The extractors are recognized by the name of their type as written in the signature, so wrapped
+extractors like `Option<HxTarget>`, and extractors imported under another name, are missed. Their
+headers can be added with `cache::add_vary` instead.
+
+# Synchronous handlers
+Handlers don't have to be `async`: a plain `fn` is called from the generated asynchronous
+handler, and its original signature is kept.
+
+# Impl blocks
+Handlers can also be associated functions, when their impl block is annotated with
+[`macro@hx_impl`]. Handlers can't take a `self` receiver.
+
 # Internals
-The macro expands to a function that returns an [`HtmxHandler<S>`].")]
+The macro expands to a `const fn` that returns a lightweight descriptor of the route, which
+implements [`HtmxHandler<S>`]. The `MethodRouter` of the handler is only built once the
+descriptor is added to a router. With the `registry`
+feature, non-generic handlers are also added to the global handler registry.")]
         #[proc_macro_attribute]
         pub fn $method(attr: TokenStream, mut item: TokenStream) -> TokenStream {
             match _route(attr, item.clone(), $enum_verb, $axum_method) {
@@ -79,6 +141,147 @@ hx_route!(hx_delete, "Delete", "delete");
 hx_route!(hx_patch, "Patch", "patch");
 hx_route!(hx_put, "Put", "put");
 
+/// A macro that allows the `hx_` macros on the associated functions of an impl block.
+///
+/// The items generated for every route are placed next to the impl block, and the generated
+/// struct of a handler `show` on `UsersController` is named `__HtmxHandler_UsersController_show`.
+/// Handlers can't take a `self` receiver, and can't use `Self` in their signature.
+///
+/// # Example
+/// ```ignore
+/// use axum_routing_htmx::{hx_get, hx_impl, HtmxRouter};
+///
+/// struct UsersController;
+///
+/// #[hx_impl]
+/// impl UsersController {
+///     #[hx_get("/users/:id")]
+///     async fn show(id: u32) -> String {
+///         Self::name(id)
+///     }
+///
+///     fn name(id: u32) -> String {
+///         format!("User {id}")
+///     }
+/// }
+///
+/// let router: axum::Router = axum::Router::new().htmx_route(UsersController::show());
+/// assert_eq!(UsersController::show().htmx_path(1), "/users/1");
+/// ```
+#[proc_macro_attribute]
+pub fn hx_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match _impl(attr, item) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+const ROUTE_MACROS: [(&str, &str, &str); 5] = [
+    ("hx_get", "Get", "get"),
+    ("hx_post", "Post", "post"),
+    ("hx_delete", "Delete", "delete"),
+    ("hx_patch", "Patch", "patch"),
+    ("hx_put", "Put", "put"),
+];
+
+fn _impl(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream2> {
+    if !attr.is_empty() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`hx_impl` doesn't take any arguments",
+        ));
+    }
+    let mut item_impl = syn::parse::<ItemImpl>(item)?;
+    if let Some((_, trait_path, _)) = &item_impl.trait_ {
+        return Err(syn::Error::new_spanned(
+            trait_path,
+            "`hx_impl` is not supported on trait implementations",
+        ));
+    }
+    if !item_impl.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item_impl.generics,
+            "`hx_impl` is not supported on generic impl blocks",
+        ));
+    }
+
+    let self_ty = item_impl.self_ty.clone();
+    let mut items = Vec::new();
+    let mut errors = Vec::<syn::Error>::new();
+    let mut constructors = Vec::new();
+    for impl_item in &mut item_impl.items {
+        let ImplItem::Fn(impl_fn) = impl_item else {
+            continue;
+        };
+        let Some(index) = impl_fn.attrs.iter().position(|attr| {
+            ROUTE_MACROS
+                .iter()
+                .any(|(name, _, _)| attr.path().segments.last().unwrap().ident == name)
+        }) else {
+            continue;
+        };
+        let attr = impl_fn.attrs.remove(index);
+        let macro_name = attr.path().segments.last().unwrap().ident.to_string();
+        let (_, enum_verb, axum_method) = ROUTE_MACROS
+            .iter()
+            .find(|(name, _, _)| *name == macro_name)
+            .unwrap();
+
+        let function = ItemFn {
+            attrs: impl_fn.attrs.clone(),
+            vis: impl_fn.vis.clone(),
+            sig: impl_fn.sig.clone(),
+            block: Box::new(impl_fn.block.clone()),
+        };
+        let expanded = attr.parse_args::<Route>().and_then(|route| {
+            check_no_self(&function.sig)?;
+            expand_route(route, function, enum_verb, axum_method, Some(&self_ty))
+        });
+        match expanded {
+            Ok((route_items, constructor)) => {
+                items.push(route_items);
+                constructors.push(constructor);
+                // The constructor takes over the name of the handler.
+                impl_fn.sig.ident = impl_handler_name(&impl_fn.sig.ident);
+                impl_fn.attrs.push(parse_quote!(#[doc(hidden)]));
+            }
+            Err(err) => errors.push(err),
+        }
+    }
+
+    for constructor in constructors {
+        item_impl.items.push(ImplItem::Verbatim(constructor));
+    }
+    let errors = errors.iter().map(syn::Error::to_compile_error);
+    Ok(quote! {
+        #item_impl
+        #(#items)*
+        #(#errors)*
+    })
+}
+
+/// Rejects `Self` in the signature of a handler in an impl block, since the signature is
+/// also used outside of the impl block.
+fn check_no_self(sig: &syn::Signature) -> syn::Result<()> {
+    fn find_self(tokens: TokenStream2) -> Option<Span> {
+        tokens.into_iter().find_map(|token| match token {
+            proc_macro2::TokenTree::Ident(ident) if ident == "Self" => Some(ident.span()),
+            proc_macro2::TokenTree::Group(group) => find_self(group.stream()),
+            _ => None,
+        })
+    }
+
+    let inputs = sig.inputs.to_token_stream();
+    let output = sig.output.to_token_stream();
+    match find_self(quote! { #inputs #output }) {
+        Some(span) => Err(syn::Error::new(
+            span,
+            "`Self` can't be used in the signature of a handler in an impl block, name the type instead",
+        )),
+        None => Ok(()),
+    }
+}
+
 fn _route(
     attr: TokenStream,
     item: TokenStream,
@@ -89,24 +292,83 @@ fn _route(
     let route = syn::parse::<Route>(attr)?;
     let function = syn::parse::<ItemFn>(item)?;
 
+    let (items, constructor) = expand_route(route, function, enum_verb, axum_method, None)?;
+    Ok(quote! {
+        #items
+        #constructor
+    })
+}
+
+/// Expands a route into the items generated next to it, and the constructor that
+/// replaces the handler. In an impl block of `self_ty`, the items are placed outside of
+/// the impl block, and the handler is renamed with [`impl_handler_name`].
+fn expand_route(
+    route: Route,
+    function: ItemFn,
+    enum_verb: &'static str,
+    axum_method: &'static str,
+    self_ty: Option<&Type>,
+) -> syn::Result<(TokenStream2, TokenStream2)> {
     // Now we can compile the route
     let route = CompiledRoute::from_route(route, &function)?;
+    let krate = &route.krate;
     let path_extractor = route.path_extractor();
     let query_extractor = route.query_extractor();
     let query_params_struct = route.query_params_struct();
     let state_type = &route.state;
     let axum_path = route.to_axum_path_string();
-    let format_path = route.to_format_path_string();
+    let htmx_path_writer = route.htmx_path_writer();
     let remaining_numbered_pats = route.remaining_pattypes_numbered(&function.sig.inputs);
     let extracted_idents = route.extracted_idents();
     let remaining_numbered_idents = remaining_numbered_pats.iter().map(|pat_type| &pat_type.pat);
     let route_docs = route.to_doc_comments();
+    let path_args = route.htmx_path_args(None);
+    // The values returned by `htmx_url` and `htmx_attrs` borrow the `&str` arguments.
+    let borrowed_path_args = route.htmx_path_args(Some(quote!('__a)));
+    let hx_target = optional_lit(&route.options.target);
+    let hx_swap = optional_lit(&route.options.swap);
+    let hx_trigger = optional_lit(&route.options.trigger);
 
     // Get the variables we need for code generation
     let fn_name = &function.sig.ident;
     let fn_output = &function.sig.output;
     let vis = &function.vis;
+    let static_path = route.to_static_path_string().map(|path| {
+        quote! {
+            /// The path of the route, which doesn't have any parameters.
+            /// Unlike `htmx_path`, it doesn't include the prefix of the router.
+            #vis const PATH: &'static str = #path;
+        }
+    });
     let asyncness = &function.sig.asyncness;
+    let htmx_only = route.options.htmx_only.as_ref().map(|(_, redirect)| {
+        let fallback = match redirect {
+            Some(path) => quote! { #krate::guard::HtmxOnly::Redirect(#path) },
+            None => quote! { #krate::guard::HtmxOnly::NotFound },
+        };
+        quote! {
+            .route_layer(#krate::__private::axum::middleware::from_fn(
+                |request: #krate::__private::axum::extract::Request,
+                 next: #krate::__private::axum::middleware::Next| {
+                    #fallback.guard(request, next)
+                },
+            ))
+        }
+    });
+    let debug_handler = match &route.options.debug {
+        Some(debug) if !function.sig.generics.params.is_empty() => {
+            return Err(syn::Error::new(
+                debug.span(),
+                "the `debug` option is not supported on generic handlers",
+            ));
+        }
+        Some(_) => Some(quote! {
+            #[#krate::__private::debug_handler(state = #state_type)]
+        }),
+        None => None,
+    };
+    // Synchronous handlers are called from the asynchronous `__inner` without awaiting them.
+    let await_handler = asyncness.as_ref().map(|_| quote! { .await });
     let (impl_generics, ty_generics, where_clause) = &function.sig.generics.split_for_impl();
     let ty_generics = ty_generics.as_turbofish();
     let fn_docs = function
@@ -114,58 +376,336 @@ fn _route(
         .iter()
         .filter(|attr| attr.path().is_ident("doc"));
     let enum_method = format_ident!("{}", enum_verb);
+    let route_info = route.to_route_info(&enum_method, &function.sig, &doc_string(&function.attrs));
     let http_method = format_ident!("{}", axum_method);
-    let htmx_struct = format_ident!("__HtmxHandler_{}", fn_name);
+    let htmx_struct = match self_ty {
+        Some(self_ty) => format_ident!("__HtmxHandler_{}_{}", type_ident(self_ty)?, fn_name),
+        None => format_ident!("__HtmxHandler_{}", fn_name),
+    };
+    let constructor = match self_ty {
+        Some(self_ty) => quote! { <#self_ty>::#fn_name },
+        None => fn_name.to_token_stream(),
+    };
+
+    // The struct is generic over the generics of the handler,
+    // which may appear in the types of the path- and query-parameters.
+    let fn_generic_args = function
+        .sig
+        .generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Type(param) => param.ident.to_token_stream(),
+            GenericParam::Lifetime(param) => param.lifetime.to_token_stream(),
+            GenericParam::Const(param) => param.ident.to_token_stream(),
+        })
+        .collect::<Vec<_>>();
+    let struct_ty = quote! { #htmx_struct<#(#fn_generic_args,)*> };
+    let phantom_types = function
+        .sig
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(param.ident.to_token_stream()),
+            GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                Some(quote! { &#lifetime () })
+            }
+            GenericParam::Const(_) => None,
+        });
+
+    // The original function becomes the associated `handler` function of the struct,
+    // which takes over the generics of the function. In an impl block, it calls the
+    // renamed handler instead, so that `Self` keeps referring to the type of the impl block.
+    let handler = match self_ty {
+        Some(self_ty) => {
+            let handler_name = impl_handler_name(fn_name);
+            let args = function
+                .sig
+                .inputs
+                .iter()
+                .enumerate()
+                .map(|(i, _arg)| format_ident!("__arg_{}", i))
+                .collect::<Vec<_>>();
+            let arg_types = function.sig.inputs.iter().map(|arg| match arg {
+                FnArg::Typed(pat_type) => &pat_type.ty,
+                FnArg::Receiver(_) => unreachable!("receivers are rejected by `from_route`"),
+            });
+            let attrs = &function.attrs;
+            quote! {
+                #(#attrs)*
+                #vis #asyncness fn handler(#(#args: #arg_types,)*) #fn_output {
+                    <#self_ty>::#handler_name(#(#args,)*) #await_handler
+                }
+            }
+        }
+        None => {
+            let mut handler = function.clone();
+            handler.sig.ident = format_ident!("handler");
+            handler.sig.generics = Default::default();
+            handler.into_token_stream()
+        }
+    };
+
+    // Generic handlers can't be registered, since their generics are unknown.
+    let registration = function.sig.generics.params.is_empty().then(|| {
+        let fn_name_str = fn_name.to_string();
+        quote! {
+            #krate::__register_htmx_handler!(
+                #krate::registry::HtmxRegistration {
+                    name: #fn_name_str,
+                    module_path: ::std::module_path!(),
+                    info: &#htmx_struct::ROUTE_INFO,
+                    state_type_id: ::std::any::TypeId::of::<#state_type>,
+                    axum_router: || {
+                        ::std::boxed::Box::new(#krate::HtmxHandler::axum_router(
+                            #constructor(),
+                        ))
+                    },
+                }
+            );
+        }
+    });
 
     // Generate the code
-    Ok(quote! {
+    let items = quote! {
+        #registration
+
+        /// A descriptor of the route, used to generate its paths. The `MethodRouter`
+        /// of the handler is only built once the route is added to a router.
         #[allow(non_camel_case_types)]
-        #vis struct #htmx_struct<S> {
-            /// The MethodRouter that must be consumed by axum.
-            method_router: ::axum::routing::MethodRouter<S>,
+        #vis struct #htmx_struct #impl_generics #where_clause {
+            /// The prefix of the router that this route is nested in.
+            prefix: &'static str,
+            _marker: ::std::marker::PhantomData<fn() -> (#(#phantom_types,)*)>,
         }
 
-        impl<S> #htmx_struct<S> {
+        impl #impl_generics ::std::clone::Clone for #struct_ty #where_clause {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl #impl_generics ::std::marker::Copy for #struct_ty #where_clause {}
+
+        #[allow(dead_code)]
+        impl #impl_generics #struct_ty #where_clause {
+            /// The path of the route, as registered in axum.
+            #vis const AXUM_PATH: &'static str = #axum_path;
+            #static_path
+            /// The HTMX method of the route.
+            #vis const METHOD: #krate::HtmxMethod =
+                #krate::HtmxMethod::#enum_method;
+
+            /// The default `hx-target` of `htmx_attrs`.
+            #vis const HX_TARGET: ::std::option::Option<&'static str> = #hx_target;
+            /// The default `hx-swap` of `htmx_attrs`.
+            #vis const HX_SWAP: ::std::option::Option<&'static str> = #hx_swap;
+            /// The default `hx-trigger` of `htmx_attrs`.
+            #vis const HX_TRIGGER: ::std::option::Option<&'static str> = #hx_trigger;
+
+            /// Sets the prefix of the router that this route is nested in, e.g. with
+            /// `HtmxRouter::htmx_nest`. It is prepended to all generated paths.
+            #vis const fn with_prefix(mut self, prefix: #krate::url::HtmxPrefix) -> Self {
+                self.prefix = prefix.as_str();
+                self
+            }
+
+            /// The prefix of the router that this route is nested in.
+            #vis const fn prefix(&self) -> &'static str {
+                self.prefix
+            }
+
+            /// The description of the route.
+            #vis const ROUTE_INFO: #krate::info::RouteInfo = #route_info;
+
+            #handler
+
             /// Generates a path according to the expected fields of the handler.
-            fn htmx_path(
+            ///
+            /// # Panics
+            /// If a path parameter is empty, `.` or `..` (which browsers would resolve to
+            /// another route), or a query parameter can't be represented in a query string,
+            /// like a sequence or a map.
+            #vis fn htmx_path(&self, #path_args) -> String {
+                self.htmx_url(#(#extracted_idents,)*).to_path()
+            }
+
+            /// Like `htmx_path`, but returns the path as a value that is only written
+            /// when it is displayed, e.g. directly into a template, without allocating.
+            ///
+            /// # Errors
+            /// Displaying it fails with `fmt::Error` (and `to_string` panics) in the cases
+            /// where `htmx_path` panics.
+            #vis fn htmx_url<'__a>(
                 &self,
-                #(#extracted_idents: impl ::std::fmt::Display,)*
-            ) -> String {
-                format!(#format_path, #(#extracted_idents,)*)
+                #borrowed_path_args
+            ) -> #krate::url::UrlFn<
+                impl ::std::ops::Fn(
+                        &mut dyn ::std::fmt::Write,
+                    ) -> ::std::result::Result<(), #krate::url::UrlError>
+                    + #krate::__private::Captures<'__a>,
+            > {
+                let __prefix = self.prefix;
+                #krate::url::UrlFn::new(
+                    move |__w: &mut dyn ::std::fmt::Write| -> ::std::result::Result<
+                        (),
+                        #krate::url::UrlError,
+                    > {
+                        __w.write_str(__prefix)?;
+                        #htmx_path_writer
+                    },
+                )
             }
 
             /// Which HTMX method this corresponds with. The `Display` interface
             /// can be used to generate the HTML attribute name.
-            fn htmx_method(&self) -> ::axum_routing_htmx::HtmxMethod {
-                ::axum_routing_htmx::HtmxMethod::#enum_method
+            #vis const fn htmx_method(&self) -> #krate::HtmxMethod {
+                Self::METHOD
             }
-        }
 
-        impl<S> ::axum_routing_htmx::HtmxHandler<S> for #htmx_struct<S> {
-            fn axum_router(self) -> (&'static str, ::axum::routing::MethodRouter<S>) {
-                (#axum_path, self.method_router)
+            /// The HTML attributes that make HTMX request this route, like
+            /// `hx-get="/item/1"`. More attributes can be added with its builder methods.
+            /// Like `htmx_url`, the path is only written when the attributes are displayed.
+            ///
+            /// # Errors
+            /// Displaying it fails with `fmt::Error` (and `to_string` panics) in the cases
+            /// where `htmx_path` panics.
+            #vis fn htmx_attrs<'__a>(
+                &self,
+                #borrowed_path_args
+            ) -> #krate::HtmxAttrs<
+                '__a,
+                #krate::url::UrlFn<
+                    impl ::std::ops::Fn(
+                            &mut dyn ::std::fmt::Write,
+                        ) -> ::std::result::Result<(), #krate::url::UrlError>
+                        + #krate::__private::Captures<'__a>,
+                >,
+            > {
+                let mut attrs = #krate::HtmxAttrs::new(
+                    self.htmx_method(),
+                    self.htmx_url(#(#extracted_idents,)*),
+                );
+                if let ::std::option::Option::Some(target) = Self::HX_TARGET {
+                    attrs = attrs.target(target);
+                }
+                if let ::std::option::Option::Some(swap) = Self::HX_SWAP {
+                    attrs = attrs.swap(swap);
+                }
+                if let ::std::option::Option::Some(trigger) = Self::HX_TRIGGER {
+                    attrs = attrs.trigger(trigger);
+                }
+                attrs
             }
-        }
 
-        #(#fn_docs)*
-        #route_docs
-        #vis fn #fn_name #impl_generics() -> #htmx_struct<#state_type> #where_clause {
+            /// An `HX-Location` response header, that makes HTMX load this route
+            /// without a full page reload.
+            /// Panics like `htmx_path`.
+            #vis fn htmx_location(&self, #path_args) -> #krate::HxLocation {
+                #krate::HxLocation::new(self.htmx_path(#(#extracted_idents,)*))
+            }
+
+            /// An `HX-Push-Url` response header, that pushes this route into the
+            /// browser's history.
+            /// Panics like `htmx_path`.
+            #vis fn htmx_push_url(&self, #path_args) -> #krate::HxPushUrl {
+                #krate::HxPushUrl(self.htmx_path(#(#extracted_idents,)*))
+            }
+
+            /// An `HX-Replace-Url` response header, that replaces the browser's
+            /// current URL with this route.
+            /// Panics like `htmx_path`.
+            #vis fn htmx_replace_url(&self, #path_args) -> #krate::HxReplaceUrl {
+                #krate::HxReplaceUrl(self.htmx_path(#(#extracted_idents,)*))
+            }
+
+            /// An `HX-Redirect` response header, that makes the browser redirect
+            /// to this route with a full page reload.
+            /// Panics like `htmx_path`.
+            #vis fn htmx_redirect(&self, #path_args) -> #krate::HxRedirect {
+                #krate::HxRedirect(self.htmx_path(#(#extracted_idents,)*))
+            }
+        }
 
-            #query_params_struct
+        impl #impl_generics #krate::HtmxHandler<#state_type> for #struct_ty
+        #where_clause
+        {
+            fn axum_router(self) -> (&'static str, #krate::__private::axum::routing::MethodRouter<#state_type>) {
+                #query_params_struct
 
-            #asyncness fn __inner #impl_generics(
-                #path_extractor
-                #query_extractor
-                #remaining_numbered_pats
-            ) #fn_output #where_clause {
-                #function
+                #debug_handler
+                async fn __inner #impl_generics(
+                    #path_extractor
+                    #query_extractor
+                    #remaining_numbered_pats
+                ) #fn_output #where_clause {
+                    <#struct_ty>::handler(#(#extracted_idents,)* #(#remaining_numbered_idents,)*) #await_handler
+                }
 
-                #fn_name #ty_generics(#(#extracted_idents,)* #(#remaining_numbered_idents,)* ).await
+                (
+                    Self::AXUM_PATH,
+                    #krate::__private::axum::routing::#http_method(__inner #ty_generics) #htmx_only,
+                )
             }
 
+            fn route_info(&self) -> &'static #krate::info::RouteInfo {
+                &Self::ROUTE_INFO
+            }
+        }
+    };
+    let constructor = quote! {
+        #(#fn_docs)*
+        #route_docs
+        #vis const fn #fn_name #impl_generics() -> #struct_ty #where_clause {
             #htmx_struct {
-                method_router: ::axum::routing::#http_method(__inner #ty_generics)
+                prefix: "",
+                _marker: ::std::marker::PhantomData,
             }
         }
-    })
+    };
+    Ok((items, constructor))
+}
+
+/// The name of a handler in an impl block, after its name is taken by the constructor.
+fn impl_handler_name(fn_name: &Ident) -> Ident {
+    format_ident!("__hx_{}", fn_name)
+}
+
+/// The identifier of the type of an impl block.
+fn type_ident(ty: &Type) -> syn::Result<&Ident> {
+    match ty {
+        Type::Path(ty) if ty.qself.is_none() => Ok(&ty.path.segments.last().unwrap().ident),
+        _ => Err(syn::Error::new_spanned(
+            ty,
+            "`hx_impl` is only supported on impl blocks of named types",
+        )),
+    }
+}
+
+/// The doc comments in `attrs`, one line per attribute.
+fn doc_string(attrs: &[Attribute]) -> String {
+    let lines = attrs.iter().filter_map(|attr| match &attr.meta {
+        Meta::NameValue(MetaNameValue {
+            path,
+            value: Expr::Lit(ExprLit {
+                lit: Lit::Str(lit), ..
+            }),
+            ..
+        }) if path.is_ident("doc") => Some(lit.value()),
+        _ => None,
+    });
+    lines
+        .map(|line| line.strip_prefix(' ').map(str::to_owned).unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Turns an optional literal into an `Option<&'static str>` expression.
+fn optional_lit(lit: &Option<LitStr>) -> TokenStream2 {
+    match lit {
+        Some(lit) => quote! { ::std::option::Option::Some(#lit) },
+        None => quote! { ::std::option::Option::None },
+    }
 }
@@ -1,5 +1,4 @@
-use quote::ToTokens;
-use syn::{token::Star, LitInt};
+use syn::token::Star;
 
 use super::*;
 
@@ -28,7 +27,7 @@ impl RouteParser {
         for path_param in path.split('/') {
             path_params.push((
                 Slash(span),
-                PathParam::new(path_param, span, Box::new(parse_quote!(()))),
+                PathParam::new(path_param, span, Box::new(parse_quote!(())))?,
             ));
         }
 
@@ -59,7 +58,7 @@ impl RouteParser {
         if split_route.len() == 2 {
             let query = split_route[1];
             for query_param in query.split('&') {
-                query_params.push(Ident::new(query_param, span));
+                query_params.push(param_ident(query_param, span)?);
             }
         }
 
@@ -70,6 +69,16 @@ impl RouteParser {
     }
 }
 
+/// The identifier of a path- or query-parameter named `str`.
+fn param_ident(str: &str, span: Span) -> syn::Result<Ident> {
+    let mut ident = syn::parse_str::<Ident>(str).map_err(|_| match str {
+        "" => syn::Error::new(span, "expected a parameter name"),
+        str => syn::Error::new(span, format!("`{str}` is not a valid parameter name")),
+    })?;
+    ident.set_span(span);
+    Ok(ident)
+}
+
 pub enum PathParam {
     WildCard(LitStr, Star, Ident, Box<Type>),
     Capture(LitStr, Colon, Ident, Box<Type>),
@@ -97,130 +106,45 @@ impl PathParam {
         }
     }
 
-    fn new(str: &str, span: Span, ty: Box<Type>) -> Self {
-        if str.starts_with(':') {
-            let str = str.strip_prefix(':').unwrap();
+    /// Parses a segment of the path. Captures can be written both in the axum 0.7 syntax,
+    /// `:capture` and `*wildcard`, and in the axum 0.8 syntax, `{capture}` and `{*wildcard}`.
+    fn new(str: &str, span: Span, ty: Box<Type>) -> syn::Result<Self> {
+        let braced = str.strip_prefix('{').and_then(|str| str.strip_suffix('}'));
+        if braced.is_none() && (str.contains('{') || str.contains('}')) {
+            return Err(syn::Error::new(
+                span,
+                "path parameters in braces must span a whole path segment",
+            ));
+        }
+
+        let (capture, wildcard) = match braced {
+            Some(str) => match str.strip_prefix('*') {
+                Some(str) => (None, Some(str)),
+                None => (Some(str), None),
+            },
+            None => (
+                str.strip_prefix(':'),
+                str.strip_prefix('*').filter(|str| !str.is_empty()),
+            ),
+        };
+
+        Ok(if let Some(str) = capture {
             Self::Capture(
                 LitStr::new(str, span),
                 Colon(span),
-                Ident::new(str, span),
+                param_ident(str, span)?,
                 ty,
             )
-        } else if str.starts_with('*') && str.len() > 1 {
-            let str = str.strip_prefix('*').unwrap();
+        } else if let Some(str) = wildcard {
             Self::WildCard(
                 LitStr::new(str, span),
                 Star(span),
-                Ident::new(str, span),
+                param_ident(str, span)?,
                 ty,
             )
         } else {
             Self::Static(LitStr::new(str, span))
-        }
-    }
-}
-
-pub struct Security(pub Vec<(LitStr, StrArray)>);
-impl Parse for Security {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let inner;
-        braced!(inner in input);
-
-        let mut arr = Vec::new();
-        while !inner.is_empty() {
-            let scheme = inner.parse::<LitStr>()?;
-            let _ = inner.parse::<Token![:]>()?;
-            let scopes = inner.parse::<StrArray>()?;
-            let _ = inner.parse::<Token![,]>().ok();
-            arr.push((scheme, scopes));
-        }
-
-        Ok(Self(arr))
-    }
-}
-
-impl std::fmt::Display for Security {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::new();
-        s.push('{');
-        for (i, (scheme, scopes)) in self.0.iter().enumerate() {
-            if i > 0 {
-                s.push_str(", ");
-            }
-            s.push_str(&scheme.value());
-            s.push_str(": ");
-            s.push_str(&scopes.to_string());
-        }
-        s.push('}');
-        f.write_str(&s)
-    }
-}
-
-pub struct Responses(pub Vec<(LitInt, Type)>);
-impl Parse for Responses {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let inner;
-        braced!(inner in input);
-
-        let mut arr = Vec::new();
-        while !inner.is_empty() {
-            let status = inner.parse::<LitInt>()?;
-            let _ = inner.parse::<Token![:]>()?;
-            let ty = inner.parse::<Type>()?;
-            let _ = inner.parse::<Token![,]>().ok();
-            arr.push((status, ty));
-        }
-
-        Ok(Self(arr))
-    }
-}
-
-impl std::fmt::Display for Responses {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::new();
-        s.push('{');
-        for (i, (status, ty)) in self.0.iter().enumerate() {
-            if i > 0 {
-                s.push_str(", ");
-            }
-            s.push_str(&status.to_string());
-            s.push_str(": ");
-            s.push_str(&ty.to_token_stream().to_string());
-        }
-        s.push('}');
-        f.write_str(&s)
-    }
-}
-
-#[derive(Clone)]
-pub struct StrArray(pub Vec<LitStr>);
-impl Parse for StrArray {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let inner;
-        bracketed!(inner in input);
-        let mut arr = Vec::new();
-        while !inner.is_empty() {
-            arr.push(inner.parse::<LitStr>()?);
-            inner.parse::<Token![,]>().ok();
-        }
-        Ok(Self(arr))
-    }
-}
-
-impl std::fmt::Display for StrArray {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::new();
-        s.push('[');
-        for (i, lit) in self.0.iter().enumerate() {
-            if i > 0 {
-                s.push_str(", ");
-            }
-            s.push('"');
-            s.push_str(&lit.value());
-            s.push('"');
-        }
-        s.push(']');
-        f.write_str(&s)
+        })
     }
 }
 
@@ -229,6 +153,7 @@ pub struct Route {
     pub query_params: Vec<Ident>,
     pub state: Option<Type>,
     pub route_lit: LitStr,
+    pub options: RouteOptions,
 }
 
 impl Parse for Route {
@@ -239,16 +164,141 @@ impl Parse for Route {
             Ok(_) => Some(input.parse::<Type>()?),
             Err(_) => None,
         };
+        let options = input.parse::<RouteOptions>()?;
 
         Ok(Route {
             path_params: route_parser.path_params,
             query_params: route_parser.query_params,
             state,
             route_lit,
+            options,
         })
     }
 }
 
+/// The swap styles accepted by `hx-swap`.
+const SWAP_STYLES: &[&str] = &[
+    "innerHTML",
+    "outerHTML",
+    "textContent",
+    "beforebegin",
+    "afterbegin",
+    "beforeend",
+    "afterend",
+    "delete",
+    "none",
+];
+
+/// The presets of the `Cache-Control` header.
+const CACHE_CONTROL_PRESETS: &[&str] = &["no-store", "no-cache", "private", "public"];
+
+/// The comma-separated `key = value` options and flags that follow the path and state.
+#[derive(Default)]
+pub struct RouteOptions {
+    pub target: Option<LitStr>,
+    pub swap: Option<LitStr>,
+    pub trigger: Option<LitStr>,
+    pub cache_control: Option<LitStr>,
+    pub debug: Option<Ident>,
+    pub htmx_only: Option<(Ident, Option<LitStr>)>,
+    pub krate: Option<syn::Path>,
+}
+
+impl RouteOptions {
+    fn set<T>(option: &mut Option<T>, key: &Ident, value: T) -> syn::Result<()> {
+        if option.replace(value).is_some() {
+            return Err(syn::Error::new(
+                key.span(),
+                format!("duplicate option `{}`", key),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Parse for RouteOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut options = Self::default();
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            if input.peek(Token![crate]) {
+                let key = Ident::from(input.parse::<Token![crate]>()?);
+                input.parse::<Token![=]>()?;
+                Self::set(&mut options.krate, &key, input.parse()?)?;
+                continue;
+            }
+
+            let key = input.parse::<Ident>()?;
+            match key.to_string().as_str() {
+                "target" => {
+                    input.parse::<Token![=]>()?;
+                    Self::set(&mut options.target, &key, input.parse()?)?;
+                }
+                "swap" => {
+                    input.parse::<Token![=]>()?;
+                    let swap = input.parse::<LitStr>()?;
+                    let value = swap.value();
+                    let style = value.split_whitespace().next().unwrap_or_default();
+                    if !SWAP_STYLES.contains(&style) {
+                        return Err(syn::Error::new(
+                            swap.span(),
+                            format!(
+                                "unknown swap style `{}`, expected one of: {}",
+                                style,
+                                SWAP_STYLES.join(", ")
+                            ),
+                        ));
+                    }
+                    Self::set(&mut options.swap, &key, swap)?;
+                }
+                "trigger" => {
+                    input.parse::<Token![=]>()?;
+                    Self::set(&mut options.trigger, &key, input.parse()?)?;
+                }
+                "cache_control" => {
+                    input.parse::<Token![=]>()?;
+                    let cache_control = input.parse::<LitStr>()?;
+                    if !CACHE_CONTROL_PRESETS.contains(&cache_control.value().as_str()) {
+                        return Err(syn::Error::new(
+                            cache_control.span(),
+                            format!(
+                                "unknown cache control preset `{}`, expected one of: {}",
+                                cache_control.value(),
+                                CACHE_CONTROL_PRESETS.join(", ")
+                            ),
+                        ));
+                    }
+                    Self::set(&mut options.cache_control, &key, cache_control)?;
+                }
+                "htmx_only" => {
+                    let redirect = match input.parse::<Option<Token![=]>>()? {
+                        Some(_) => Some(input.parse::<LitStr>()?),
+                        None => None,
+                    };
+                    Self::set(&mut options.htmx_only, &key, (key.clone(), redirect))?;
+                }
+                "debug" => {
+                    Self::set(&mut options.debug, &key, key.clone())?;
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!(
+                        "unknown option `{}`, expected one of: target, swap, trigger, cache_control, htmx_only, debug, crate",
+                        key
+                    ),
+                    ))
+                }
+            }
+        }
+        Ok(options)
+    }
+}
+
 mod kw {
     syn::custom_keyword!(with);
 }
@@ -26,14 +26,12 @@ async fn index_handler() -> impl IntoResponse {
                 <script src=\"https://unpkg.com/htmx.org@2.0.2/dist/htmx.js\" crossorigin=\"anonymous\" />
             </head>
             <body>
-                <h1 {}=\"{}\" hx-trigger=\"load\">Loading...</h1>
-                <button {}=\"{}\" id=\"button-1\">Click me!</button>
+                <h1 {}>Loading...</h1>
+                <button {} id=\"button-1\">Click me!</button>
             </body>
         </html>",
-        title.htmx_method(),
-        title.htmx_path(),
-        button.htmx_method(),
-        button.htmx_path(1),
+        title.htmx_attrs().trigger("load"),
+        button.htmx_attrs(1),
     ))
 }
 
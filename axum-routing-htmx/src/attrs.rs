@@ -0,0 +1,132 @@
+//! Rendering of the HTMX attributes of an HTML element.
+
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Write},
+};
+
+use crate::HtmxMethod;
+
+/// Escapes everything written to it for use inside a double-quoted HTML attribute.
+struct AttrEscaper<W> {
+    inner: W,
+}
+
+impl<W: Write> Write for AttrEscaper<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut rest = s;
+        while let Some(i) = rest.find(['&', '"', '\'', '<', '>']) {
+            self.inner.write_str(&rest[..i])?;
+            self.inner.write_str(match rest.as_bytes()[i] {
+                b'&' => "&amp;",
+                b'"' => "&quot;",
+                b'\'' => "&#39;",
+                b'<' => "&lt;",
+                _ => "&gt;",
+            })?;
+            rest = &rest[i + 1..];
+        }
+        self.inner.write_str(rest)
+    }
+}
+
+fn write_attr(f: &mut fmt::Formatter<'_>, name: impl Display, value: impl Display) -> fmt::Result {
+    write!(f, "{name}=\"")?;
+    write!(AttrEscaper { inner: &mut *f }, "{value}")?;
+    f.write_char('"')
+}
+
+/// The HTMX attributes of an element that requests a route, like
+/// `hx-get="/item/1" hx-target="#item"`.
+///
+/// It is created by the `htmx_attrs` method of a handler generated by the `hx_` macros.
+/// Its [`Display`] implementation renders the attributes, with their values escaped.
+///
+/// ```
+/// use axum_routing_htmx::{HtmxAttrs, HtmxMethod};
+///
+/// let attrs = HtmxAttrs::new(HtmxMethod::Get, "/search?q=a&b")
+///     .target("#results")
+///     .trigger("keyup changed delay:500ms");
+/// assert_eq!(
+///     attrs.to_string(),
+///     r##"hx-get="/search?q=a&amp;b" hx-target="#results" hx-trigger="keyup changed delay:500ms""##,
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct HtmxAttrs<'a, P = String> {
+    method: HtmxMethod,
+    path: P,
+    target: Option<Cow<'a, str>>,
+    swap: Option<Cow<'a, str>>,
+    trigger: Option<Cow<'a, str>>,
+    include: Option<Cow<'a, str>>,
+    vals: Option<serde_json::Value>,
+}
+
+impl<'a, P: Display> HtmxAttrs<'a, P> {
+    /// The attributes that make HTMX request `path` with `method`.
+    pub fn new(method: HtmxMethod, path: P) -> Self {
+        Self {
+            method,
+            path,
+            target: None,
+            swap: None,
+            trigger: None,
+            include: None,
+            vals: None,
+        }
+    }
+
+    /// Sets `hx-target`, the element to swap the response into.
+    pub fn target(mut self, target: impl Into<Cow<'a, str>>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Sets `hx-swap`, how the response is swapped into the target.
+    pub fn swap(mut self, swap: impl Into<Cow<'a, str>>) -> Self {
+        self.swap = Some(swap.into());
+        self
+    }
+
+    /// Sets `hx-trigger`, the event that triggers the request.
+    pub fn trigger(mut self, trigger: impl Into<Cow<'a, str>>) -> Self {
+        self.trigger = Some(trigger.into());
+        self
+    }
+
+    /// Sets `hx-include`, the additional elements whose values are submitted with the request.
+    pub fn include(mut self, include: impl Into<Cow<'a, str>>) -> Self {
+        self.include = Some(include.into());
+        self
+    }
+
+    /// Sets `hx-vals`, the values to submit with the request.
+    pub fn vals(mut self, vals: serde_json::Value) -> Self {
+        self.vals = Some(vals);
+        self
+    }
+}
+
+impl<P: Display> Display for HtmxAttrs<'_, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_attr(f, self.method, &self.path)?;
+        for (name, value) in [
+            ("hx-target", &self.target),
+            ("hx-swap", &self.swap),
+            ("hx-trigger", &self.trigger),
+            ("hx-include", &self.include),
+        ] {
+            if let Some(value) = value {
+                f.write_char(' ')?;
+                write_attr(f, name, value)?;
+            }
+        }
+        if let Some(vals) = &self.vals {
+            f.write_char(' ')?;
+            write_attr(f, "hx-vals", vals)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+//! The caching headers of HTMX routes.
+//!
+//! A response that depends on HTMX request headers must list them in its `Vary` header,
+//! or a cache may serve a fragment as the full page. Routes added with
+//! [`HtmxRouter`](crate::HtmxRouter) add the headers in [`RouteInfo::vary`] to the `Vary`
+//! header of their responses, and set the `Cache-Control` header to the preset in
+//! [`RouteInfo::cache_control`], unless the handler sets it itself.
+
+use axum::{
+    http::{header, HeaderMap, HeaderValue},
+    middleware,
+    response::Response,
+    routing::MethodRouter,
+};
+
+use crate::RouteInfo;
+
+/// Adds `names` to the `Vary` header, skipping those that are already in it.
+pub fn add_vary(headers: &mut HeaderMap, names: &[&'static str]) {
+    for name in names {
+        let present = headers
+            .get_all(header::VARY)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|value| {
+                let value = value.trim();
+                value == "*" || value.eq_ignore_ascii_case(name)
+            });
+        if !present {
+            headers.append(header::VARY, HeaderValue::from_static(name));
+        }
+    }
+}
+
+/// Adds the caching headers of the route to the responses of `method_router`.
+pub(crate) fn route_headers<S>(
+    method_router: MethodRouter<S>,
+    info: &'static RouteInfo,
+) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    with_headers(method_router, info.vary, info.cache_control)
+}
+
+/// Adds `vary` to the `Vary` header and the `cache_control` preset to the responses of
+/// `method_router`.
+pub(crate) fn with_headers<S>(
+    method_router: MethodRouter<S>,
+    vary: &'static [&'static str],
+    cache_control: Option<&'static str>,
+) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    if vary.is_empty() && cache_control.is_none() {
+        return method_router;
+    }
+    method_router.layer(middleware::map_response(
+        move |mut response: Response| async move {
+            add_vary(response.headers_mut(), vary);
+            if let Some(cache_control) = cache_control {
+                if !response.headers().contains_key(header::CACHE_CONTROL) {
+                    response.headers_mut().insert(
+                        header::CACHE_CONTROL,
+                        HeaderValue::from_static(cache_control),
+                    );
+                }
+            }
+            response
+        },
+    ))
+}
@@ -0,0 +1,144 @@
+//! Extractors for the request headers sent by HTMX.
+//!
+//! All extractors are infallible: a missing or malformed header is extracted as
+//! `false` or `None`. They can be used as regular handler arguments next to the
+//! path- and query-parameters of a typed route:
+//!
+//! ```
+//! use axum_routing_htmx::{hx_get, HtmxRequest, HxTarget};
+//!
+//! #[hx_get("/item/:id")]
+//! async fn item_handler(id: u32, htmx: HtmxRequest, HxTarget(target): HxTarget) -> String {
+//!     format!("Item {id} (htmx: {}, target: {target:?})", htmx.is_htmx)
+//! }
+//! ```
+
+use std::convert::Infallible;
+
+#[cfg(feature = "axum-07")]
+use axum::async_trait;
+use axum::{
+    extract::FromRequestParts,
+    http::{request::Parts, HeaderMap, HeaderName, Uri},
+};
+
+use crate::headers;
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &HeaderName) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn header_bool(headers: &HeaderMap, name: &HeaderName) -> bool {
+    header_str(headers, name) == Some("true")
+}
+
+fn header_string(headers: &HeaderMap, name: &HeaderName) -> Option<String> {
+    header_str(headers, name).map(str::to_owned)
+}
+
+fn header_uri(headers: &HeaderMap, name: &HeaderName) -> Option<Uri> {
+    header_str(headers, name).and_then(|value| value.parse().ok())
+}
+
+macro_rules! hx_extractor {
+    ($(#[$attr:meta])* $name:ident($ty:ty) = $header:ident, $parse:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name(pub $ty);
+
+        #[cfg_attr(feature = "axum-07", async_trait)]
+        impl<S> FromRequestParts<S> for $name
+        where
+            S: Send + Sync,
+        {
+            type Rejection = Infallible;
+
+            async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+                Ok(Self($parse(&parts.headers, &headers::$header)))
+            }
+        }
+    };
+}
+
+hx_extractor!(
+    /// Whether the request was made by HTMX, from the `HX-Request` header.
+    HxRequest(bool) = HX_REQUEST, header_bool
+);
+hx_extractor!(
+    /// Whether the request was made by a boosted element, from the `HX-Boosted` header.
+    HxBoosted(bool) = HX_BOOSTED, header_bool
+);
+hx_extractor!(
+    /// The current URL of the browser, from the `HX-Current-URL` header.
+    HxCurrentUrl(Option<Uri>) = HX_CURRENT_URL, header_uri
+);
+hx_extractor!(
+    /// Whether the request restores history after a cache miss, from the
+    /// `HX-History-Restore-Request` header.
+    HxHistoryRestoreRequest(bool) = HX_HISTORY_RESTORE_REQUEST, header_bool
+);
+hx_extractor!(
+    /// The user response to an `hx-prompt`, from the `HX-Prompt` header.
+    HxPrompt(Option<String>) = HX_PROMPT, header_string
+);
+hx_extractor!(
+    /// The `id` of the target element, from the `HX-Target` header.
+    HxTarget(Option<String>) = HX_TARGET, header_string
+);
+hx_extractor!(
+    /// The `id` of the triggered element, from the `HX-Trigger` header.
+    HxTrigger(Option<String>) = HX_TRIGGER, header_string
+);
+hx_extractor!(
+    /// The `name` of the triggered element, from the `HX-Trigger-Name` header.
+    HxTriggerName(Option<String>) = HX_TRIGGER_NAME, header_string
+);
+
+/// All HTMX request headers at once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HtmxRequest {
+    /// `HX-Request`
+    pub is_htmx: bool,
+    /// `HX-Boosted`
+    pub boosted: bool,
+    /// `HX-Current-URL`
+    pub current_url: Option<Uri>,
+    /// `HX-History-Restore-Request`
+    pub history_restore_request: bool,
+    /// `HX-Prompt`
+    pub prompt: Option<String>,
+    /// `HX-Target`
+    pub target: Option<String>,
+    /// `HX-Trigger`
+    pub trigger: Option<String>,
+    /// `HX-Trigger-Name`
+    pub trigger_name: Option<String>,
+}
+
+impl HtmxRequest {
+    /// Reads the HTMX headers from a [`HeaderMap`].
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            is_htmx: header_bool(headers, &headers::HX_REQUEST),
+            boosted: header_bool(headers, &headers::HX_BOOSTED),
+            current_url: header_uri(headers, &headers::HX_CURRENT_URL),
+            history_restore_request: header_bool(headers, &headers::HX_HISTORY_RESTORE_REQUEST),
+            prompt: header_string(headers, &headers::HX_PROMPT),
+            target: header_string(headers, &headers::HX_TARGET),
+            trigger: header_string(headers, &headers::HX_TRIGGER),
+            trigger_name: header_string(headers, &headers::HX_TRIGGER_NAME),
+        }
+    }
+}
+
+#[cfg_attr(feature = "axum-07", async_trait)]
+impl<S> FromRequestParts<S> for HtmxRequest
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
@@ -0,0 +1,47 @@
+//! The guard of routes that only serve HTMX requests.
+//!
+//! A route with the `htmx_only` option rejects requests without the `HX-Request: true`
+//! header, like a user opening the URL of a fragment directly:
+//!
+//! ```
+//! use axum_routing_htmx::hx_get;
+//!
+//! // Responds with `404 Not Found` to requests that aren't made by HTMX.
+//! #[hx_get("/rows", htmx_only)]
+//! async fn rows() {}
+//!
+//! // Redirects requests that aren't made by HTMX to `/`.
+//! #[hx_get("/sidebar", htmx_only = "/")]
+//! async fn sidebar() {}
+//! ```
+
+use axum::{
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Redirect, Response},
+};
+
+use crate::extract::HtmxRequest;
+
+/// What a route with the `htmx_only` option responds to requests that aren't made by HTMX.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HtmxOnly {
+    /// Responds with `404 Not Found`.
+    NotFound,
+    /// Redirects to the path with `303 See Other`.
+    Redirect(&'static str),
+}
+
+impl HtmxOnly {
+    /// Passes HTMX requests on to `next`, and responds to all others with the fallback.
+    pub async fn guard(self, request: Request, next: Next) -> Response {
+        if HtmxRequest::from_headers(request.headers()).is_htmx {
+            return next.run(request).await;
+        }
+        match self {
+            HtmxOnly::NotFound => StatusCode::NOT_FOUND.into_response(),
+            HtmxOnly::Redirect(path) => Redirect::to(path).into_response(),
+        }
+    }
+}
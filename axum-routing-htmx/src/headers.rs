@@ -0,0 +1,44 @@
+//! Names of the headers used by HTMX.
+//!
+//! See the [HTMX reference](https://htmx.org/reference/#headers) for their meaning.
+
+use axum::http::HeaderName;
+
+/// Always `true` on requests made by HTMX.
+pub const HX_REQUEST: HeaderName = HeaderName::from_static("hx-request");
+/// `true` if the request is made via an element using `hx-boost`.
+pub const HX_BOOSTED: HeaderName = HeaderName::from_static("hx-boosted");
+/// The current URL of the browser.
+pub const HX_CURRENT_URL: HeaderName = HeaderName::from_static("hx-current-url");
+/// `true` if the request is for history restoration after a miss in the local history cache.
+pub const HX_HISTORY_RESTORE_REQUEST: HeaderName =
+    HeaderName::from_static("hx-history-restore-request");
+/// The user response to an `hx-prompt`.
+pub const HX_PROMPT: HeaderName = HeaderName::from_static("hx-prompt");
+/// The `id` of the target element if it exists.
+pub const HX_TARGET: HeaderName = HeaderName::from_static("hx-target");
+/// The `name` of the triggered element if it exists.
+pub const HX_TRIGGER_NAME: HeaderName = HeaderName::from_static("hx-trigger-name");
+/// On requests, the `id` of the triggered element if it exists.
+/// On responses, the client-side events to trigger.
+pub const HX_TRIGGER: HeaderName = HeaderName::from_static("hx-trigger");
+/// Client-side redirect without a full page reload.
+pub const HX_LOCATION: HeaderName = HeaderName::from_static("hx-location");
+/// Pushes a new URL into the history stack.
+pub const HX_PUSH_URL: HeaderName = HeaderName::from_static("hx-push-url");
+/// Client-side redirect with a full page reload.
+pub const HX_REDIRECT: HeaderName = HeaderName::from_static("hx-redirect");
+/// If `true`, the client does a full refresh of the page.
+pub const HX_REFRESH: HeaderName = HeaderName::from_static("hx-refresh");
+/// Replaces the current URL in the location bar.
+pub const HX_REPLACE_URL: HeaderName = HeaderName::from_static("hx-replace-url");
+/// Overrides the `hx-select` of the triggering element.
+pub const HX_RESELECT: HeaderName = HeaderName::from_static("hx-reselect");
+/// Overrides how the response will be swapped.
+pub const HX_RESWAP: HeaderName = HeaderName::from_static("hx-reswap");
+/// Overrides the target of the content update.
+pub const HX_RETARGET: HeaderName = HeaderName::from_static("hx-retarget");
+/// Client-side events to trigger after the settle step.
+pub const HX_TRIGGER_AFTER_SETTLE: HeaderName = HeaderName::from_static("hx-trigger-after-settle");
+/// Client-side events to trigger after the swap step.
+pub const HX_TRIGGER_AFTER_SWAP: HeaderName = HeaderName::from_static("hx-trigger-after-swap");
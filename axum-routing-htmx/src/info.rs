@@ -0,0 +1,54 @@
+//! Introspection of the routes generated by the `hx_` macros.
+//!
+//! Every handler describes its route with a [`RouteInfo`], which can be used to build
+//! route listings or to check the routes in tests:
+//!
+//! ```
+//! use axum_routing_htmx::{hx_get, HtmxHandler, HtmxMethod};
+//!
+//! /// Shows an item.
+//! #[hx_get("/item/:id?amount")]
+//! async fn item(id: u32, amount: Option<u32>) {}
+//!
+//! let info = item().route_info();
+//! assert_eq!(info.method, HtmxMethod::Get);
+//! assert_eq!(info.route, "/item/:id?amount");
+//! assert_eq!(info.path_params[0].name, "id");
+//! assert_eq!(info.query_params[0].ty, "Option<u32>");
+//! assert_eq!(info.state_type, "()");
+//! assert_eq!(info.docs, "Shows an item.");
+//! ```
+
+use crate::HtmxMethod;
+
+/// A description of a route, generated by the `hx_` macros.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteInfo {
+    /// The HTMX method of the route.
+    pub method: HtmxMethod,
+    /// The path of the route, as registered in axum.
+    pub path: &'static str,
+    /// The route as written in the attribute, including its query parameters.
+    pub route: &'static str,
+    /// The path parameters, in order.
+    pub path_params: &'static [ParamInfo],
+    /// The query parameters, in order.
+    pub query_params: &'static [ParamInfo],
+    /// The state type of the route.
+    pub state_type: &'static str,
+    /// The doc comments of the handler.
+    pub docs: &'static str,
+    /// The HTMX request headers that the response depends on, for its `Vary` header.
+    pub vary: &'static [&'static str],
+    /// The `Cache-Control` preset of the route.
+    pub cache_control: Option<&'static str>,
+}
+
+/// A path- or query-parameter of a route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParamInfo {
+    /// The name of the parameter.
+    pub name: &'static str,
+    /// The type of the parameter in the handler, as written in its signature.
+    pub ty: &'static str,
+}
@@ -0,0 +1,95 @@
+//! Full-page layouts around HTMX fragments.
+//!
+//! A handler that returns a fragment can also serve the full page, when its response is
+//! wrapped in a layout for every request that expects one: requests that aren't made by
+//! HTMX (like a reload of a URL pushed with `hx-push-url`), boosted requests, and history
+//! restore requests. See [`HtmxRouter::htmx_route_with_layout`](crate::HtmxRouter::htmx_route_with_layout).
+//!
+//! ```
+//! # #[cfg(feature = "axum-08")]
+//! # extern crate axum08 as axum;
+//! use axum::response::Html;
+//! use axum_routing_htmx::{hx_get, HtmxRouter};
+//!
+//! #[hx_get("/items")]
+//! async fn items() -> Html<&'static str> {
+//!     Html("<ul><li>Item</li></ul>")
+//! }
+//!
+//! let router: axum::Router = axum::Router::new().htmx_route_with_layout(items(), |fragment| {
+//!     Html(format!("<html><body>{fragment}</body></html>"))
+//! });
+//! ```
+//!
+//! [`apply_layout`] can also be used with [`axum::middleware::from_fn`] to wrap the
+//! responses of a whole router.
+
+use axum::{
+    body::{self, Body},
+    extract::Request,
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::extract::HtmxRequest;
+
+/// The HTMX request headers that decide whether a response is wrapped in the layout.
+pub const VARY: &[&str] = &["HX-Request", "HX-Boosted", "HX-History-Restore-Request"];
+
+/// The size limit of the fragments that are wrapped in a layout, in bytes.
+///
+/// Larger responses with a `Content-Length` are returned unchanged, and streamed responses
+/// that exceed it are replaced by a `500 Internal Server Error`.
+pub const BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Whether the response to a request with these HTMX headers must be a full page.
+pub fn needs_layout(htmx: &HtmxRequest) -> bool {
+    !htmx.is_htmx || htmx.boosted || htmx.history_restore_request
+}
+
+/// Whether the response is an HTML fragment that can be wrapped in a layout: a successful
+/// `text/html` response, that isn't larger than the [`BODY_LIMIT`].
+fn is_fragment(response: &Response) -> bool {
+    let headers = response.headers();
+    let is_html = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("text/html"));
+    let too_large = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok()?.parse::<usize>().ok())
+        .is_some_and(|length| length > BODY_LIMIT);
+    response.status().is_success() && is_html && !too_large
+}
+
+/// Runs the request, and wraps a successful `text/html` response with a body in `layout`
+/// if the request expects a full page. Other responses, like JSON or `204 No Content`,
+/// are returned unchanged.
+pub async fn apply_layout<L, R>(layout: L, request: Request, next: Next) -> Response
+where
+    L: Fn(String) -> R,
+    R: IntoResponse,
+{
+    let htmx = HtmxRequest::from_headers(request.headers());
+    let response = next.run(request).await;
+    if !needs_layout(&htmx) || !is_fragment(&response) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = body::to_bytes(body, BODY_LIMIT).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    if bytes.is_empty() {
+        return Response::from_parts(parts, Body::empty());
+    }
+    let fragment = match String::from_utf8(bytes.to_vec()) {
+        Ok(fragment) => fragment,
+        Err(_) => return Response::from_parts(parts, Body::from(bytes)),
+    };
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.remove(header::CONTENT_TYPE);
+    (parts, layout(fragment)).into_response()
+}
@@ -7,6 +7,8 @@
 //!
 //! ```
 #![doc = include_str!("../examples/basic.rs")]
+//! # #[cfg(feature = "axum-08")]
+//! # extern crate axum08 as axum;
 //! ```
 //!
 //! Some valid url's as get-methods are:
@@ -15,18 +17,76 @@
 //! - `/item/1?offset=3`
 //! - `/item/500`
 //!
+//! ## Axum versions
+//! The crate supports both axum 0.7 and axum 0.8, selected with exactly one of the
+//! `axum-07` (default) and `axum-08` features. The macros accept both the `:capture`/`*wildcard`
+//! and the `{capture}`/`{*wildcard}` path syntax, and register the routes with the syntax of
+//! the selected version.
+//!
+//! ## Registry
+//! With the `registry` feature, all handlers are collected in a global registry, and
+//! can be mounted at once with `HtmxRouter::htmx_routes_all` instead of one by one.
+//!
+
+#[cfg(all(feature = "axum-07", feature = "axum-08"))]
+compile_error!("the features `axum-07` and `axum-08` are mutually exclusive");
+#[cfg(not(any(feature = "axum-07", feature = "axum-08")))]
+compile_error!("one of the features `axum-07` and `axum-08` must be enabled");
+
+#[cfg(all(feature = "axum-08", not(feature = "axum-07")))]
+extern crate axum08 as axum;
 
 use std::fmt::Display;
 
-use axum::routing::MethodRouter;
+#[cfg(any(feature = "axum-07", feature = "axum-08"))]
+use axum::{response::IntoResponse, routing::MethodRouter};
+
+pub mod attrs;
+#[cfg(any(feature = "axum-07", feature = "axum-08"))]
+pub mod cache;
+#[cfg(any(feature = "axum-07", feature = "axum-08"))]
+pub mod extract;
+#[cfg(any(feature = "axum-07", feature = "axum-08"))]
+pub mod guard;
+#[cfg(any(feature = "axum-07", feature = "axum-08"))]
+pub mod headers;
+pub mod info;
+#[cfg(any(feature = "axum-07", feature = "axum-08"))]
+pub mod layout;
+#[cfg(any(feature = "axum-07", feature = "axum-08"))]
+pub mod redirect;
+#[cfg(all(feature = "registry", any(feature = "axum-07", feature = "axum-08")))]
+pub mod registry;
+#[cfg(any(feature = "axum-07", feature = "axum-08"))]
+pub mod response;
+pub mod url;
+
+pub use attrs::HtmxAttrs;
+#[cfg(any(feature = "axum-07", feature = "axum-08"))]
+pub use extract::{
+    HtmxRequest, HxBoosted, HxCurrentUrl, HxHistoryRestoreRequest, HxPrompt, HxRequest, HxTarget,
+    HxTrigger, HxTriggerName,
+};
+pub use info::{ParamInfo, RouteInfo};
+#[cfg(any(feature = "axum-07", feature = "axum-08"))]
+pub use response::{
+    HtmxResponse, HxError, HxEvent, HxLocation, HxPushUrl, HxRedirect, HxRefresh, HxReplaceUrl,
+    HxReselect, HxResponseTrigger, HxReswap, HxRetarget, HxTriggerAfterSettle, HxTriggerAfterSwap,
+    SwapOption,
+};
+pub use url::HtmxPrefix;
 
 /// The trait expected by the Router to add HTMX routes.
+#[cfg(any(feature = "axum-07", feature = "axum-08"))]
 pub trait HtmxHandler<S> {
     fn axum_router(self) -> (&'static str, MethodRouter<S>);
+
+    /// The description of the route.
+    fn route_info(&self) -> &'static RouteInfo;
 }
 
 #[non_exhaustive]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// The HTTP verbs supported by HTMX.
 pub enum HtmxMethod {
     Get,
@@ -48,20 +108,74 @@ impl Display for HtmxMethod {
     }
 }
 
-pub use axum_routing_htmx_macros::{hx_delete, hx_get, hx_patch, hx_post, hx_put};
+/// The dependencies of the generated code, so that it works without depending on them directly.
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "axum-07")]
+    pub use ::axum;
+    #[cfg(all(feature = "axum-08", not(feature = "axum-07")))]
+    pub use ::axum08 as axum;
+    pub use ::serde;
+
+    #[cfg(any(feature = "axum-07", feature = "axum-08"))]
+    pub use self::axum::http;
+
+    #[cfg(feature = "axum-07")]
+    pub use axum_macros::debug_handler;
+    #[cfg(all(feature = "axum-08", not(feature = "axum-07")))]
+    pub use axum_macros08::debug_handler;
+
+    /// Lets the `impl Trait` returned by `htmx_url` and `htmx_attrs` capture the lifetime
+    /// of their `&str` arguments, which edition 2021 only allows for lifetimes in its bounds.
+    pub trait Captures<'a> {}
+
+    impl<T: ?Sized> Captures<'_> for T {}
+}
+
+pub use axum_routing_htmx_macros::{hx_delete, hx_get, hx_impl, hx_patch, hx_post, hx_put};
 
 /// A trait that allows typed routes, created with the `hx_` macros to
 /// be added to an axum router.
+#[cfg(any(feature = "axum-07", feature = "axum-08"))]
 pub trait HtmxRouter: Sized {
     /// The state type of the router.
     type State: Send + Sync + Clone + 'static;
 
     /// Add an HTMX route to the router.
     ///
-    /// Typed handlers are functions that return [`HtmxHandler`].
+    /// Typed handlers are functions that return [`HtmxHandler`]. The caching headers of the
+    /// route are added to its responses, see the [`cache`] module.
     fn htmx_route(self, handler: impl HtmxHandler<Self::State>) -> Self;
+
+    /// Nest a router of HTMX routes at `prefix`.
+    ///
+    /// The routes are registered relative to the prefix, so their generated paths must
+    /// include it too: pass the same [`HtmxPrefix`] to the `with_prefix` method of the
+    /// handlers to generate them.
+    fn htmx_nest(self, prefix: HtmxPrefix, router: Self) -> Self;
+
+    /// Add an HTMX route to the router, whose successful HTML responses are wrapped in `layout`
+    /// when the request expects a full page: when it isn't made by HTMX, is boosted, or
+    /// restores the history. See the [`layout`] module.
+    fn htmx_route_with_layout<L, R>(
+        self,
+        handler: impl HtmxHandler<Self::State>,
+        layout: L,
+    ) -> Self
+    where
+        L: Fn(String) -> R + Clone + Send + Sync + 'static,
+        R: IntoResponse + 'static;
+
+    /// Add all HTMX routes of the [`registry`] with the state type of the router.
+    ///
+    /// Generic handlers are not registered, and must still be added with
+    /// [`htmx_route`](HtmxRouter::htmx_route). A handler must not be added both ways:
+    /// axum panics on the overlapping route.
+    #[cfg(feature = "registry")]
+    fn htmx_routes_all(self) -> Self;
 }
 
+#[cfg(any(feature = "axum-07", feature = "axum-08"))]
 impl<S> HtmxRouter for axum::Router<S>
 where
     S: Send + Sync + Clone + 'static,
@@ -69,7 +183,47 @@ where
     type State = S;
 
     fn htmx_route(self, handler: impl HtmxHandler<Self::State>) -> Self {
+        let info = handler.route_info();
         let (path, method_router) = handler.axum_router();
-        self.route(path, method_router)
+        self.route(path, cache::route_headers(method_router, info))
     }
+
+    fn htmx_nest(self, prefix: HtmxPrefix, router: Self) -> Self {
+        self.nest(prefix.as_str(), router)
+    }
+
+    fn htmx_route_with_layout<L, R>(self, handler: impl HtmxHandler<Self::State>, layout: L) -> Self
+    where
+        L: Fn(String) -> R + Clone + Send + Sync + 'static,
+        R: IntoResponse + 'static,
+    {
+        let info = handler.route_info();
+        let (path, method_router) = handler.axum_router();
+        let method_router = method_router.layer(axum::middleware::from_fn(
+            move |request: axum::extract::Request, next: axum::middleware::Next| {
+                layout::apply_layout(layout.clone(), request, next)
+            },
+        ));
+        let method_router = cache::with_headers(method_router, layout::VARY, None);
+        self.route(path, cache::route_headers(method_router, info))
+    }
+
+    #[cfg(feature = "registry")]
+    fn htmx_routes_all(self) -> Self {
+        registry::handlers_with_state::<S>()
+            .filter_map(|handler| {
+                let (path, method_router) = handler.axum_router::<S>()?;
+                Some((path, cache::route_headers(method_router, handler.info)))
+            })
+            .fold(self, |router, (path, method_router)| {
+                router.route(path, method_router)
+            })
+    }
+}
+
+#[cfg(not(feature = "registry"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_htmx_handler {
+    ($registration:expr) => {};
 }
@@ -0,0 +1,148 @@
+//! Translation of redirects for HTMX requests.
+//!
+//! HTMX follows a `3xx` redirect transparently, and swaps the page it is redirected to into
+//! the element that made the request, like a login page after the session expired. The
+//! [`HtmxRedirectLayer`] rewrites such redirects into an `HX-Redirect` (or `HX-Location`)
+//! header with a `200 OK` status, so HTMX loads the page instead. Requests that aren't made
+//! by HTMX are redirected as usual, so middleware that redirects can be used unchanged:
+//!
+//! ```
+//! # #[cfg(feature = "axum-08")]
+//! # extern crate axum08 as axum;
+//! use axum::{response::Redirect, routing::get};
+//! use axum_routing_htmx::redirect::HtmxRedirectLayer;
+//!
+//! let router: axum::Router = axum::Router::new()
+//!     .route("/private", get(|| async { Redirect::to("/login") }))
+//!     .layer(HtmxRedirectLayer::new());
+//! ```
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderName, StatusCode},
+    response::Response,
+};
+use pin_project_lite::pin_project;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{cache, extract::HtmxRequest, headers};
+
+/// A [`Layer`] that rewrites the `3xx` redirects of HTMX requests, see the [module](self).
+#[derive(Debug, Clone, Copy)]
+pub struct HtmxRedirectLayer {
+    location: bool,
+}
+
+impl HtmxRedirectLayer {
+    /// Rewrites redirects into an `HX-Redirect` header, a redirect with a full page reload.
+    pub fn new() -> Self {
+        Self { location: false }
+    }
+
+    /// Rewrites redirects into an `HX-Location` header instead, a redirect without a full
+    /// page reload.
+    pub fn with_location(mut self) -> Self {
+        self.location = true;
+        self
+    }
+}
+
+impl Default for HtmxRedirectLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Layer<S> for HtmxRedirectLayer {
+    type Service = HtmxRedirect<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HtmxRedirect {
+            inner,
+            location: self.location,
+        }
+    }
+}
+
+/// The [`Service`] created by [`HtmxRedirectLayer`].
+#[derive(Debug, Clone, Copy)]
+pub struct HtmxRedirect<S> {
+    inner: S,
+    location: bool,
+}
+
+impl<S> Service<Request> for HtmxRedirect<S>
+where
+    S: Service<Request, Response = Response>,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let is_htmx = HtmxRequest::from_headers(request.headers()).is_htmx;
+        let header = match self.location {
+            true => headers::HX_LOCATION,
+            false => headers::HX_REDIRECT,
+        };
+        ResponseFuture {
+            inner: self.inner.call(request),
+            header: is_htmx.then_some(header),
+        }
+    }
+}
+
+pin_project! {
+    /// The response future of [`HtmxRedirect`].
+    pub struct ResponseFuture<F> {
+        #[pin]
+        inner: F,
+        header: Option<HeaderName>,
+    }
+}
+
+impl<F, E> Future for ResponseFuture<F>
+where
+    F: Future<Output = Result<Response, E>>,
+{
+    type Output = Result<Response, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let response = std::task::ready!(this.inner.poll(cx))?;
+        Poll::Ready(Ok(rewrite_redirect(response, this.header.take())))
+    }
+}
+
+/// Adds `HX-Request` to the `Vary` header of a redirect, since its response depends on it.
+/// For HTMX requests, also moves the `Location` into `header`, with a `200 OK` status.
+fn rewrite_redirect(mut response: Response, header: Option<HeaderName>) -> Response {
+    if !response.status().is_redirection() {
+        return response;
+    }
+    cache::add_vary(response.headers_mut(), &["HX-Request"]);
+    let Some(header) = header else {
+        return response;
+    };
+    if !response.headers().contains_key(header::LOCATION) {
+        return response;
+    }
+    let (mut parts, _body) = response.into_parts();
+    let location = parts.headers.remove(header::LOCATION).unwrap();
+    parts.status = StatusCode::OK;
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(header, location);
+    Response::from_parts(parts, Body::empty())
+}
@@ -0,0 +1,78 @@
+//! A global registry of all handlers created with the `hx_` macros.
+//!
+//! With the `registry` feature, every non-generic handler registers itself here, so it
+//! doesn't have to be added to a router by hand. [`HtmxRouter::htmx_routes_all`] mounts
+//! all registered handlers of the router's state type:
+//!
+//! ```
+//! # #[cfg(feature = "axum-08")]
+//! # extern crate axum08 as axum;
+//! use axum_routing_htmx::{hx_get, registry, HtmxRouter};
+//!
+//! #[hx_get("/item/:id")]
+//! async fn item(id: u32) {}
+//!
+//! let router: axum::Router = axum::Router::new().htmx_routes_all();
+//! assert!(registry::handlers().any(|handler| handler.name == "item"));
+//! ```
+//!
+//! [`HtmxRouter::htmx_routes_all`]: crate::HtmxRouter::htmx_routes_all
+
+use std::any::{Any, TypeId};
+
+use axum::routing::MethodRouter;
+
+#[doc(hidden)]
+pub use inventory;
+
+use crate::RouteInfo;
+
+/// A handler registered by the `hx_` macros.
+#[derive(Debug)]
+pub struct HtmxRegistration {
+    /// The name of the handler function.
+    pub name: &'static str,
+    /// The module the handler was defined in.
+    pub module_path: &'static str,
+    /// The description of the route.
+    pub info: &'static RouteInfo,
+    #[doc(hidden)]
+    pub state_type_id: fn() -> TypeId,
+    #[doc(hidden)]
+    pub axum_router: fn() -> Box<dyn Any>,
+}
+
+inventory::collect!(HtmxRegistration);
+
+impl HtmxRegistration {
+    /// Whether the route has the state type `S`.
+    pub fn has_state<S: 'static>(&self) -> bool {
+        (self.state_type_id)() == TypeId::of::<S>()
+    }
+
+    /// The path and `MethodRouter` of the route, if it has the state type `S`.
+    pub fn axum_router<S: 'static>(&self) -> Option<(&'static str, MethodRouter<S>)> {
+        (self.axum_router)()
+            .downcast::<(&'static str, MethodRouter<S>)>()
+            .ok()
+            .map(|router| *router)
+    }
+}
+
+/// All registered handlers, in no particular order.
+pub fn handlers() -> impl Iterator<Item = &'static HtmxRegistration> {
+    inventory::iter::<HtmxRegistration>.into_iter()
+}
+
+/// All registered handlers with the state type `S`.
+pub fn handlers_with_state<S: 'static>() -> impl Iterator<Item = &'static HtmxRegistration> {
+    handlers().filter(|handler| handler.has_state::<S>())
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_htmx_handler {
+    ($registration:expr) => {
+        $crate::registry::inventory::submit! { $registration }
+    };
+}
@@ -0,0 +1,515 @@
+//! Response headers understood by HTMX.
+//!
+//! Every header is its own [`IntoResponseParts`] type, so they can be returned
+//! alongside a body from any handler. [`HtmxResponse`] composes several of them:
+//!
+//! ```
+//! use axum_routing_htmx::{hx_post, HtmxResponse, SwapOption};
+//!
+//! #[hx_post("/todo")]
+//! async fn create_todo() -> (HtmxResponse, &'static str) {
+//!     (
+//!         HtmxResponse::new()
+//!             .retarget("#todo-list")
+//!             .reswap(SwapOption::BeforeEnd)
+//!             .trigger("todo-created"),
+//!         "<li>New todo</li>",
+//!     )
+//! }
+//! ```
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use axum::{
+    http::{header::InvalidHeaderValue, HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, IntoResponseParts, Response, ResponseParts},
+};
+use serde::Serialize;
+
+use crate::headers;
+
+/// The error returned when a value cannot be used as a header value.
+#[derive(Debug)]
+pub struct HxError(InvalidHeaderValue);
+
+impl Display for HxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid HTMX header value: {}", self.0)
+    }
+}
+
+impl std::error::Error for HxError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl IntoResponse for HxError {
+    fn into_response(self) -> Response {
+        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+    }
+}
+
+fn insert_header(
+    mut res: ResponseParts,
+    name: HeaderName,
+    value: &str,
+) -> Result<ResponseParts, HxError> {
+    let value = HeaderValue::from_str(value).map_err(HxError)?;
+    res.headers_mut().insert(name, value);
+    Ok(res)
+}
+
+/// How HTMX swaps the response into the target element.
+///
+/// See [`hx-swap`](https://htmx.org/attributes/hx-swap/).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SwapOption {
+    #[serde(rename = "innerHTML")]
+    InnerHtml,
+    #[serde(rename = "outerHTML")]
+    OuterHtml,
+    #[serde(rename = "textContent")]
+    TextContent,
+    #[serde(rename = "beforebegin")]
+    BeforeBegin,
+    #[serde(rename = "afterbegin")]
+    AfterBegin,
+    #[serde(rename = "beforeend")]
+    BeforeEnd,
+    #[serde(rename = "afterend")]
+    AfterEnd,
+    #[serde(rename = "delete")]
+    Delete,
+    #[serde(rename = "none")]
+    None,
+}
+
+impl Display for SwapOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SwapOption::InnerHtml => "innerHTML",
+            SwapOption::OuterHtml => "outerHTML",
+            SwapOption::TextContent => "textContent",
+            SwapOption::BeforeBegin => "beforebegin",
+            SwapOption::AfterBegin => "afterbegin",
+            SwapOption::BeforeEnd => "beforeend",
+            SwapOption::AfterEnd => "afterend",
+            SwapOption::Delete => "delete",
+            SwapOption::None => "none",
+        })
+    }
+}
+
+/// The `HX-Location` header: a client-side redirect without a full page reload.
+///
+/// Without any options, only the path is sent. Otherwise the header is sent as
+/// a JSON object, as described in the [HTMX reference](https://htmx.org/headers/hx-location/).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HxLocation {
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    swap: Option<SwapOption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    select: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    values: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    headers: BTreeMap<String, String>,
+}
+
+impl HxLocation {
+    /// Redirects to the given path.
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// The path to redirect to.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The source element of the request.
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// An event that "triggered" the request.
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// The target to swap the response into.
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// How the response will be swapped in relative to the target.
+    pub fn swap(mut self, swap: SwapOption) -> Self {
+        self.swap = Some(swap);
+        self
+    }
+
+    /// Allows you to select the content you want swapped from a response.
+    pub fn select(mut self, select: impl Into<String>) -> Self {
+        self.select = Some(select.into());
+        self
+    }
+
+    /// Values to submit with the request.
+    pub fn values(mut self, values: serde_json::Value) -> Self {
+        self.values = Some(values);
+        self
+    }
+
+    /// Adds a header to submit with the request.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    fn is_path_only(&self) -> bool {
+        self.source.is_none()
+            && self.event.is_none()
+            && self.target.is_none()
+            && self.swap.is_none()
+            && self.select.is_none()
+            && self.values.is_none()
+            && self.headers.is_empty()
+    }
+}
+
+impl From<String> for HxLocation {
+    fn from(path: String) -> Self {
+        Self::new(path)
+    }
+}
+
+impl From<&str> for HxLocation {
+    fn from(path: &str) -> Self {
+        Self::new(path)
+    }
+}
+
+impl IntoResponseParts for HxLocation {
+    type Error = HxError;
+
+    fn into_response_parts(self, res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        if self.is_path_only() {
+            insert_header(res, headers::HX_LOCATION, &self.path)
+        } else {
+            let json = serde_json::to_string(&self).expect("location is always valid JSON");
+            insert_header(res, headers::HX_LOCATION, &json)
+        }
+    }
+}
+
+macro_rules! hx_string_header {
+    ($(#[$attr:meta])* $name:ident = $header:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name(pub String);
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                Self(value.to_owned())
+            }
+        }
+
+        impl IntoResponseParts for $name {
+            type Error = HxError;
+
+            fn into_response_parts(self, res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+                insert_header(res, headers::$header, &self.0)
+            }
+        }
+    };
+}
+
+hx_string_header!(
+    /// The `HX-Push-Url` header: pushes a new URL into the history stack.
+    ///
+    /// The value `false` prevents the browser's history from being updated.
+    HxPushUrl = HX_PUSH_URL
+);
+hx_string_header!(
+    /// The `HX-Replace-Url` header: replaces the current URL in the location bar.
+    ///
+    /// The value `false` prevents the browser's current URL from being updated.
+    HxReplaceUrl = HX_REPLACE_URL
+);
+hx_string_header!(
+    /// The `HX-Redirect` header: a client-side redirect with a full page reload.
+    HxRedirect = HX_REDIRECT
+);
+hx_string_header!(
+    /// The `HX-Retarget` header: a CSS selector that overrides the target of the content update.
+    HxRetarget = HX_RETARGET
+);
+hx_string_header!(
+    /// The `HX-Reselect` header: a CSS selector that overrides the `hx-select` of the
+    /// triggering element.
+    HxReselect = HX_RESELECT
+);
+
+/// The `HX-Refresh` header: the client does a full refresh of the page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HxRefresh;
+
+impl IntoResponseParts for HxRefresh {
+    type Error = HxError;
+
+    fn into_response_parts(self, res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        insert_header(res, headers::HX_REFRESH, "true")
+    }
+}
+
+/// The `HX-Reswap` header: overrides how the response will be swapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HxReswap(pub SwapOption);
+
+impl From<SwapOption> for HxReswap {
+    fn from(swap: SwapOption) -> Self {
+        Self(swap)
+    }
+}
+
+impl IntoResponseParts for HxReswap {
+    type Error = HxError;
+
+    fn into_response_parts(self, res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        insert_header(res, headers::HX_RESWAP, &self.0.to_string())
+    }
+}
+
+/// A client-side event, triggered by one of the `HX-Trigger` response headers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HxEvent {
+    name: String,
+    detail: Option<serde_json::Value>,
+}
+
+impl HxEvent {
+    /// An event without any details.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            detail: None,
+        }
+    }
+
+    /// An event with details, which are passed to the event listeners as `evt.detail`.
+    pub fn with_detail(name: impl Into<String>, detail: serde_json::Value) -> Self {
+        Self {
+            name: name.into(),
+            detail: Some(detail),
+        }
+    }
+}
+
+impl From<String> for HxEvent {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<&str> for HxEvent {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+/// Encodes the events as a comma-separated list if none carry details,
+/// and as a JSON object otherwise.
+fn encode_events(events: &[HxEvent]) -> String {
+    if events.iter().all(|event| event.detail.is_none()) {
+        return events
+            .iter()
+            .map(|event| event.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+    }
+    let map = events
+        .iter()
+        .map(|event| {
+            (
+                event.name.clone(),
+                event.detail.clone().unwrap_or(serde_json::Value::Null),
+            )
+        })
+        .collect::<serde_json::Map<_, _>>();
+    serde_json::Value::Object(map).to_string()
+}
+
+macro_rules! hx_trigger_header {
+    ($(#[$attr:meta])* $name:ident = $header:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Default, PartialEq)]
+        pub struct $name(pub Vec<HxEvent>);
+
+        impl<E: Into<HxEvent>> From<E> for $name {
+            fn from(event: E) -> Self {
+                Self(vec![event.into()])
+            }
+        }
+
+        impl IntoResponseParts for $name {
+            type Error = HxError;
+
+            fn into_response_parts(self, res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+                if self.0.is_empty() {
+                    return Ok(res);
+                }
+                insert_header(res, headers::$header, &encode_events(&self.0))
+            }
+        }
+    };
+}
+
+hx_trigger_header!(
+    /// The `HX-Trigger` response header: triggers client-side events as soon as
+    /// the response is received.
+    HxResponseTrigger = HX_TRIGGER
+);
+hx_trigger_header!(
+    /// The `HX-Trigger-After-Settle` header: triggers client-side events after the settle step.
+    HxTriggerAfterSettle = HX_TRIGGER_AFTER_SETTLE
+);
+hx_trigger_header!(
+    /// The `HX-Trigger-After-Swap` header: triggers client-side events after the swap step.
+    HxTriggerAfterSwap = HX_TRIGGER_AFTER_SWAP
+);
+
+/// A builder that composes the HTMX response headers.
+///
+/// It can be used on its own as a response without a body, or as the response
+/// parts next to a body.
+#[derive(Debug, Clone, Default)]
+pub struct HtmxResponse {
+    location: Option<HxLocation>,
+    push_url: Option<HxPushUrl>,
+    replace_url: Option<HxReplaceUrl>,
+    redirect: Option<HxRedirect>,
+    refresh: Option<HxRefresh>,
+    reswap: Option<HxReswap>,
+    retarget: Option<HxRetarget>,
+    reselect: Option<HxReselect>,
+    trigger: HxResponseTrigger,
+    trigger_after_settle: HxTriggerAfterSettle,
+    trigger_after_swap: HxTriggerAfterSwap,
+}
+
+impl HtmxResponse {
+    /// An empty set of HTMX response headers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the `HX-Location` header.
+    pub fn location(mut self, location: impl Into<HxLocation>) -> Self {
+        self.location = Some(location.into());
+        self
+    }
+
+    /// Sets the `HX-Push-Url` header.
+    pub fn push_url(mut self, url: impl Into<HxPushUrl>) -> Self {
+        self.push_url = Some(url.into());
+        self
+    }
+
+    /// Sets the `HX-Replace-Url` header.
+    pub fn replace_url(mut self, url: impl Into<HxReplaceUrl>) -> Self {
+        self.replace_url = Some(url.into());
+        self
+    }
+
+    /// Sets the `HX-Redirect` header.
+    pub fn redirect(mut self, url: impl Into<HxRedirect>) -> Self {
+        self.redirect = Some(url.into());
+        self
+    }
+
+    /// Sets the `HX-Refresh` header.
+    pub fn refresh(mut self) -> Self {
+        self.refresh = Some(HxRefresh);
+        self
+    }
+
+    /// Sets the `HX-Reswap` header.
+    pub fn reswap(mut self, swap: impl Into<HxReswap>) -> Self {
+        self.reswap = Some(swap.into());
+        self
+    }
+
+    /// Sets the `HX-Retarget` header.
+    pub fn retarget(mut self, target: impl Into<HxRetarget>) -> Self {
+        self.retarget = Some(target.into());
+        self
+    }
+
+    /// Sets the `HX-Reselect` header.
+    pub fn reselect(mut self, select: impl Into<HxReselect>) -> Self {
+        self.reselect = Some(select.into());
+        self
+    }
+
+    /// Adds an event to the `HX-Trigger` header.
+    pub fn trigger(mut self, event: impl Into<HxEvent>) -> Self {
+        self.trigger.0.push(event.into());
+        self
+    }
+
+    /// Adds an event to the `HX-Trigger-After-Settle` header.
+    pub fn trigger_after_settle(mut self, event: impl Into<HxEvent>) -> Self {
+        self.trigger_after_settle.0.push(event.into());
+        self
+    }
+
+    /// Adds an event to the `HX-Trigger-After-Swap` header.
+    pub fn trigger_after_swap(mut self, event: impl Into<HxEvent>) -> Self {
+        self.trigger_after_swap.0.push(event.into());
+        self
+    }
+}
+
+impl IntoResponseParts for HtmxResponse {
+    type Error = HxError;
+
+    fn into_response_parts(self, res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        let res = self.location.into_response_parts(res)?;
+        let res = self.push_url.into_response_parts(res)?;
+        let res = self.replace_url.into_response_parts(res)?;
+        let res = self.redirect.into_response_parts(res)?;
+        let res = self.refresh.into_response_parts(res)?;
+        let res = self.reswap.into_response_parts(res)?;
+        let res = self.retarget.into_response_parts(res)?;
+        let res = self.reselect.into_response_parts(res)?;
+        let res = self.trigger.into_response_parts(res)?;
+        let res = self.trigger_after_settle.into_response_parts(res)?;
+        self.trigger_after_swap.into_response_parts(res)
+    }
+}
+
+impl IntoResponse for HtmxResponse {
+    fn into_response(self) -> Response {
+        (self, ()).into_response()
+    }
+}
@@ -0,0 +1,625 @@
+//! Percent-encoding of the values interpolated into generated paths.
+//!
+//! The `htmx_path` methods generated by the `hx_` macros wrap every argument in one
+//! of these types, so that a value can never change the structure of the URL:
+//!
+//! ```
+//! use axum_routing_htmx::url::{PathSegment, PathWildcard, QueryValue};
+//!
+//! assert_eq!(PathSegment("a b/c").to_string(), "a%20b%2Fc");
+//! assert_eq!(PathWildcard("a b/c").to_string(), "a%20b/c");
+//! assert_eq!(QueryValue("1&x=2").to_string(), "1%26x%3D2");
+//! ```
+//!
+//! Empty path segments and the dot segments `.` and `..` are rejected, since browsers
+//! resolve them before sending the request, even when they are percent-encoded:
+//!
+//! ```
+//! use std::fmt::Write;
+//!
+//! use axum_routing_htmx::url::{PathSegment, PathWildcard};
+//!
+//! assert!(write!(String::new(), "{}", PathSegment("..")).is_err());
+//! assert!(write!(String::new(), "{}", PathWildcard("a/../b")).is_err());
+//! ```
+
+use std::fmt::{self, Display, Write};
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{ser, Serialize};
+
+/// Everything except the unreserved characters of RFC 3986.
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Segment,
+    Wildcard,
+    Query,
+}
+
+/// Percent-encodes everything written to it before passing it on to the inner writer.
+struct Encoder<W> {
+    inner: W,
+    encoding: Encoding,
+    /// The length of the current path segment, as long as it only consists of dots.
+    dots: Option<usize>,
+    /// The number of dots of the rejected path segment, if any.
+    rejected: Option<usize>,
+}
+
+impl<W: Write> Encoder<W> {
+    fn new(inner: W, encoding: Encoding) -> Self {
+        Self {
+            inner,
+            encoding,
+            dots: Some(0),
+            rejected: None,
+        }
+    }
+
+    fn write_segment(&mut self, s: &str) -> fmt::Result {
+        if !s.bytes().all(|byte| byte == b'.') {
+            self.dots = None;
+        }
+        self.dots = self.dots.map(|dots| dots + s.len());
+        utf8_percent_encode(s, SEGMENT).try_for_each(|chunk| self.inner.write_str(chunk))
+    }
+
+    /// Ends the current path segment, and rejects it if it is empty, `.` or `..`.
+    fn end_segment(&mut self) -> fmt::Result {
+        if self.encoding == Encoding::Query {
+            return Ok(());
+        }
+        if let Some(dots @ 0..=2) = self.dots {
+            self.rejected = Some(dots);
+            return Err(fmt::Error);
+        }
+        self.dots = Some(0);
+        Ok(())
+    }
+
+    /// Writes `value`, and ends its last path segment.
+    fn write_value(&mut self, value: impl Display) -> fmt::Result {
+        write!(self, "{value}")?;
+        self.end_segment()
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match self.encoding {
+            Encoding::Segment => self.write_segment(s),
+            Encoding::Wildcard => {
+                let mut pieces = s.split('/');
+                self.write_segment(pieces.next().unwrap_or_default())?;
+                for piece in pieces {
+                    self.end_segment()?;
+                    self.inner.write_char('/')?;
+                    self.write_segment(piece)?;
+                }
+                Ok(())
+            }
+            Encoding::Query => form_urlencoded::byte_serialize(s.as_bytes())
+                .try_for_each(|chunk| self.inner.write_str(chunk)),
+        }
+    }
+}
+
+fn write_encoded(
+    f: &mut fmt::Formatter<'_>,
+    encoding: Encoding,
+    value: impl Display,
+) -> fmt::Result {
+    Encoder::new(f, encoding).write_value(value)
+}
+
+/// Writes the value of the path parameter `key` to `writer`, like [`PathSegment`].
+///
+/// The value is serialized with serde, like axum's `Path` extractor deserializes it:
+/// enums are written as their (renamed) variant name.
+///
+/// # Errors
+/// If the value is empty, `.` or `..`, or has no path segment representation, like
+/// sequences and maps.
+pub fn write_segment<T: Serialize + ?Sized>(
+    writer: &mut dyn Write,
+    key: &str,
+    value: &T,
+) -> Result<(), UrlError> {
+    write_path(writer, Encoding::Segment, key, value)
+}
+
+/// Writes the value of the wildcard path parameter `key` to `writer`, like [`PathWildcard`].
+///
+/// # Errors
+/// Like [`write_segment`], but for each of its `/`-separated segments.
+pub fn write_wildcard<T: Serialize + ?Sized>(
+    writer: &mut dyn Write,
+    key: &str,
+    value: &T,
+) -> Result<(), UrlError> {
+    write_path(writer, Encoding::Wildcard, key, value)
+}
+
+fn write_path<T: Serialize + ?Sized>(
+    writer: &mut dyn Write,
+    encoding: Encoding,
+    key: &str,
+    value: &T,
+) -> Result<(), UrlError> {
+    let mut path = PathValue {
+        encoder: Encoder::new(writer, encoding),
+    };
+    value
+        .serialize(ValueSerializer { sink: &mut path })
+        .map_err(|err| match err {
+            UrlError::Query { message, .. } => UrlError::Path {
+                key: key.to_owned(),
+                message,
+            },
+            err => err,
+        })
+}
+
+/// A single path segment, e.g. the value of an `:id` capture.
+///
+/// All characters except `A-Z a-z 0-9 - . _ ~` are percent-encoded, including `/`.
+/// Formatting fails if the value is empty, `.` or `..`.
+#[derive(Debug, Clone, Copy)]
+pub struct PathSegment<T>(pub T);
+
+impl<T: Display> Display for PathSegment<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_encoded(f, Encoding::Segment, &self.0)
+    }
+}
+
+/// The remainder of a path, e.g. the value of a `*rest` capture.
+///
+/// Like [`PathSegment`], but `/` is kept as a separator, and every segment between them
+/// is checked.
+#[derive(Debug, Clone, Copy)]
+pub struct PathWildcard<T>(pub T);
+
+impl<T: Display> Display for PathWildcard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_encoded(f, Encoding::Wildcard, &self.0)
+    }
+}
+
+/// The value of a query parameter, encoded as `application/x-www-form-urlencoded`.
+#[derive(Debug, Clone, Copy)]
+pub struct QueryValue<T>(pub T);
+
+impl<T: Display> Display for QueryValue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_encoded(f, Encoding::Query, &self.0)
+    }
+}
+
+/// The prefix of a nested router, prepended to the generated paths of its handlers.
+///
+/// The same prefix is passed to [`HtmxRouter::htmx_nest`](crate::HtmxRouter::htmx_nest)
+/// and to the `with_prefix` method of the handlers, so declaring it once as a constant keeps
+/// the generated paths in sync with where the routes are mounted:
+///
+/// ```
+/// # #[cfg(feature = "axum-08")]
+/// # extern crate axum08 as axum;
+/// use axum_routing_htmx::{hx_get, HtmxPrefix, HtmxRouter};
+///
+/// const ADMIN: HtmxPrefix = HtmxPrefix::new("/admin");
+///
+/// #[hx_get("/users/:id")]
+/// async fn user(id: u32) {}
+///
+/// let router: axum::Router =
+///     axum::Router::new().htmx_nest(ADMIN, axum::Router::new().htmx_route(user()));
+/// assert_eq!(user().with_prefix(ADMIN).htmx_path(1), "/admin/users/1");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HtmxPrefix(&'static str);
+
+impl HtmxPrefix {
+    /// A prefix like `/admin`.
+    ///
+    /// # Panics
+    /// If the prefix doesn't start with a `/`, or ends with one. In a constant, this is
+    /// a compile-time error.
+    pub const fn new(prefix: &'static str) -> Self {
+        let bytes = prefix.as_bytes();
+        assert!(
+            !bytes.is_empty() && bytes[0] == b'/',
+            "a prefix must start with a `/`"
+        );
+        assert!(
+            bytes[bytes.len() - 1] != b'/',
+            "a prefix must not end with a `/`"
+        );
+        Self(prefix)
+    }
+
+    /// The prefix as a string.
+    pub const fn as_str(self) -> &'static str {
+        self.0
+    }
+}
+
+impl Display for HtmxPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// The error returned when a URL cannot be written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UrlError {
+    /// The underlying writer returned an error.
+    Fmt,
+    /// The value of a path parameter would change the structure of the path.
+    Path {
+        /// The name of the path parameter.
+        key: String,
+        /// What went wrong.
+        message: String,
+    },
+    /// The value of a query parameter cannot be represented in a query string.
+    Query {
+        /// The name of the query parameter.
+        key: String,
+        /// What went wrong.
+        message: String,
+    },
+}
+
+impl Display for UrlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UrlError::Fmt => f.write_str("an error occurred when formatting the URL"),
+            UrlError::Path { key, message } => {
+                write!(f, "invalid path parameter `{key}`: {message}")
+            }
+            UrlError::Query { key, message } => {
+                write!(f, "cannot serialize query parameter `{key}`: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for UrlError {}
+
+impl From<fmt::Error> for UrlError {
+    fn from(_: fmt::Error) -> Self {
+        UrlError::Fmt
+    }
+}
+
+impl ser::Error for UrlError {
+    fn custom<T: Display>(msg: T) -> Self {
+        UrlError::Query {
+            key: String::new(),
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// Appends query parameters to a path, separated by `?` and `&`.
+///
+/// Values are serialized with serde, the same way `serde_urlencoded` serializes the
+/// fields of a struct. Anything written by it is accepted by axum's `Query` extractor:
+/// `None` values are left out, enums are written as their (renamed) variant name, and
+/// values without a query string representation, like sequences and maps, are an error.
+pub struct QueryWriter<W> {
+    inner: W,
+    first: bool,
+}
+
+impl<W: Write> QueryWriter<W> {
+    /// Starts the query, right after the path has been written to `inner`.
+    pub fn new(inner: W) -> Self {
+        Self { inner, first: true }
+    }
+
+    /// Appends `key=value`, unless the value is `None`.
+    pub fn push<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), UrlError> {
+        let mut pair = QueryPair { query: self, key };
+        value
+            .serialize(ValueSerializer { sink: &mut pair })
+            .map_err(|err| match err {
+                UrlError::Query { message, .. } => UrlError::Query {
+                    key: key.to_owned(),
+                    message,
+                },
+                err => err,
+            })
+    }
+
+    fn write_pair(&mut self, key: &str, value: impl Display) -> Result<(), UrlError> {
+        let separator = if self.first { '?' } else { '&' };
+        self.first = false;
+        write!(
+            self.inner,
+            "{separator}{}={}",
+            QueryValue(key),
+            QueryValue(value)
+        )?;
+        Ok(())
+    }
+}
+
+/// Where [`ValueSerializer`] writes the value of a parameter.
+trait ValueSink {
+    fn write_value(&mut self, value: impl Display) -> Result<(), UrlError>;
+
+    fn write_none(&mut self) -> Result<(), UrlError>;
+}
+
+/// A query parameter, written as `key=value`, and left out if it is `None`.
+struct QueryPair<'a, W> {
+    query: &'a mut QueryWriter<W>,
+    key: &'a str,
+}
+
+impl<W: Write> ValueSink for QueryPair<'_, W> {
+    fn write_value(&mut self, value: impl Display) -> Result<(), UrlError> {
+        self.query.write_pair(self.key, value)
+    }
+
+    fn write_none(&mut self) -> Result<(), UrlError> {
+        Ok(())
+    }
+}
+
+/// A path parameter, written as percent-encoded path segments.
+struct PathValue<'a> {
+    encoder: Encoder<&'a mut dyn Write>,
+}
+
+impl ValueSink for PathValue<'_> {
+    fn write_value(&mut self, value: impl Display) -> Result<(), UrlError> {
+        self.encoder
+            .write_value(value)
+            .map_err(|_| match self.encoder.rejected {
+                Some(0) => ser::Error::custom("empty path segment"),
+                Some(dots) => {
+                    ser::Error::custom(format_args!("dot segment `{}`", ".".repeat(dots)))
+                }
+                None => UrlError::Fmt,
+            })
+    }
+
+    fn write_none(&mut self) -> Result<(), UrlError> {
+        Err(ser::Error::custom("unsupported value"))
+    }
+}
+
+/// Serializes the value of a single path- or query-parameter.
+struct ValueSerializer<'a, S> {
+    sink: &'a mut S,
+}
+
+impl<S> ValueSerializer<'_, S> {
+    fn unsupported<T>(self) -> Result<T, UrlError> {
+        Err(ser::Error::custom("unsupported value"))
+    }
+}
+
+impl<S: ValueSink> ser::Serializer for ValueSerializer<'_, S> {
+    type Ok = ();
+    type Error = UrlError;
+    type SerializeSeq = ser::Impossible<(), UrlError>;
+    type SerializeTuple = ser::Impossible<(), UrlError>;
+    type SerializeTupleStruct = ser::Impossible<(), UrlError>;
+    type SerializeTupleVariant = ser::Impossible<(), UrlError>;
+    type SerializeMap = ser::Impossible<(), UrlError>;
+    type SerializeStruct = ser::Impossible<(), UrlError>;
+    type SerializeStructVariant = ser::Impossible<(), UrlError>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), UrlError> {
+        self.sink.write_value(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), UrlError> {
+        match std::str::from_utf8(v) {
+            Ok(v) => self.sink.write_value(v),
+            Err(err) => Err(ser::Error::custom(err)),
+        }
+    }
+
+    fn serialize_none(self) -> Result<(), UrlError> {
+        self.sink.write_none()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), UrlError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), UrlError> {
+        self.unsupported()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), UrlError> {
+        self.sink.write_value(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), UrlError> {
+        self.sink.write_value(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), UrlError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), UrlError> {
+        self.unsupported()
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, UrlError> {
+        self.unsupported()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, UrlError> {
+        self.unsupported()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, UrlError> {
+        self.unsupported()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, UrlError> {
+        self.unsupported()
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, UrlError> {
+        self.unsupported()
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, UrlError> {
+        self.unsupported()
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, UrlError> {
+        self.unsupported()
+    }
+}
+
+/// A URL, written on demand by a closure generated by the `hx_` macros.
+///
+/// It is returned by the `htmx_url` method of a handler. Its [`Display`] implementation
+/// writes the URL straight into the formatter, and fails with [`fmt::Error`] if it can't
+/// be written, see [`UrlError`].
+#[derive(Clone, Copy)]
+pub struct UrlFn<F>(F);
+
+impl<F> UrlFn<F>
+where
+    F: Fn(&mut dyn Write) -> Result<(), UrlError>,
+{
+    /// Wraps the closure that writes the URL.
+    pub fn new(f: F) -> Self {
+        Self(f)
+    }
+
+    /// Writes the URL to `writer`.
+    pub fn write_to(&self, writer: &mut dyn Write) -> Result<(), UrlError> {
+        (self.0)(writer)
+    }
+
+    /// Writes the URL to a new `String`.
+    ///
+    /// # Panics
+    /// If the URL can't be written, see [`UrlError`].
+    pub fn to_path(&self) -> String {
+        let mut path = String::new();
+        if let Err(err) = self.write_to(&mut path) {
+            panic!("{err}");
+        }
+        path
+    }
+}
+
+impl<F> Display for UrlFn<F>
+where
+    F: Fn(&mut dyn Write) -> Result<(), UrlError>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f).map_err(|_| fmt::Error)
+    }
+}
@@ -3,14 +3,38 @@
 
 use std::net::TcpListener;
 
+#[cfg(feature = "axum-08")]
+extern crate axum08 as axum;
+
 use axum::{
     extract::{Path, State},
     routing::get,
     Form, Json,
 };
-use axum_routing_htmx::HtmxRouter;
+use axum_routing_htmx::{HtmxPrefix, HtmxRouter};
 use axum_routing_htmx_macros::{hx_get, hx_post};
-use axum_test::TestServer;
+#[cfg(feature = "axum-07")]
+use axum_test::{TestResponse, TestServer};
+#[cfg(feature = "axum-08")]
+use axum_test08::{TestResponse, TestServer};
+
+/// The expected path of a route in axum, in the path syntax of the enabled axum version.
+fn axum_path(axum_07: &'static str, axum_08: &'static str) -> &'static str {
+    if cfg!(feature = "axum-08") {
+        axum_08
+    } else {
+        axum_07
+    }
+}
+
+/// Sends a `GET` request for a generated path. `TestServer` does not accept a query in the
+/// path, so it is passed separately.
+async fn get_path(server: &TestServer, path: &str) -> TestResponse {
+    match path.split_once('?') {
+        Some((path, query)) => server.get(path).add_raw_query_param(query).await,
+        None => server.get(path).await,
+    }
+}
 
 /// This is a handler that is documented!
 #[hx_get("/hello/:id?user_id&name")]
@@ -89,13 +113,13 @@ async fn test_normal() {
 
     let handler = generic_handler_with_complex_options::<u32>();
     assert_eq!(
-        handler.htmx_path(123, 321, "John"),
+        handler.htmx_path(123, "321", "John"),
         "/hello/123?user_id=321&name=John"
     );
     assert_eq!(handler.htmx_method(), axum_routing_htmx::HtmxMethod::Get);
     assert_eq!(
         axum_routing_htmx::HtmxHandler::axum_router(handler).0,
-        "/hello/:id"
+        axum_path("/hello/:id", "/hello/{id}")
     );
 }
 
@@ -120,3 +144,852 @@ async fn test_wildcard() {
     response.assert_status_ok();
     assert_eq!(response.json::<String>(), "foo/bar");
 }
+
+#[hx_get("/htmx/:id")]
+async fn htmx_headers(
+    id: u32,
+    htmx: axum_routing_htmx::HtmxRequest,
+    axum_routing_htmx::HxTarget(target): axum_routing_htmx::HxTarget,
+) -> String {
+    format!(
+        "{id} {} {} {:?} {:?}",
+        htmx.is_htmx, htmx.boosted, htmx.trigger, target
+    )
+}
+
+#[tokio::test]
+async fn test_htmx_request_headers() {
+    use axum::http::{HeaderName, HeaderValue};
+
+    let router: axum::Router = axum::Router::new().htmx_route(htmx_headers());
+    let server = TestServer::new(router).unwrap();
+
+    let response = server.get("/htmx/1").await;
+    response.assert_status_ok();
+    response.assert_text("1 false false None None");
+
+    let response = server
+        .get("/htmx/2")
+        .add_header(
+            HeaderName::from_static("hx-request"),
+            HeaderValue::from_static("true"),
+        )
+        .add_header(
+            HeaderName::from_static("hx-trigger"),
+            HeaderValue::from_static("button-2"),
+        )
+        .add_header(
+            HeaderName::from_static("hx-target"),
+            HeaderValue::from_static("list"),
+        )
+        .await;
+    response.assert_status_ok();
+    response.assert_text("2 true false Some(\"button-2\") Some(\"list\")");
+}
+
+#[hx_post("/htmx-response")]
+async fn htmx_response() -> (axum_routing_htmx::HtmxResponse, &'static str) {
+    use axum_routing_htmx::{HtmxResponse, HxEvent, HxLocation, SwapOption};
+
+    (
+        HtmxResponse::new()
+            .location(HxLocation::new("/todos").target("#list"))
+            .push_url("/todos")
+            .reswap(SwapOption::OuterHtml)
+            .trigger("first")
+            .trigger(HxEvent::with_detail("second", 2.into()))
+            .trigger_after_swap("swapped"),
+        "Done!",
+    )
+}
+
+#[tokio::test]
+async fn test_htmx_response_headers() {
+    let router: axum::Router = axum::Router::new().htmx_route(htmx_response());
+    let server = TestServer::new(router).unwrap();
+
+    let response = server.post("/htmx-response").await;
+    response.assert_status_ok();
+    response.assert_text("Done!");
+    assert_eq!(
+        response.header("hx-location"),
+        r##"{"path":"/todos","target":"#list"}"##
+    );
+    assert_eq!(response.header("hx-push-url"), "/todos");
+    assert_eq!(response.header("hx-reswap"), "outerHTML");
+    assert_eq!(
+        response.header("hx-trigger"),
+        r#"{"first":null,"second":2}"#
+    );
+    assert_eq!(response.header("hx-trigger-after-swap"), "swapped");
+    assert!(response.headers().get("hx-redirect").is_none());
+}
+
+#[hx_post("/redirect/:id")]
+async fn redirect(id: u32) -> (axum_routing_htmx::HtmxResponse, String) {
+    let three = three();
+    (
+        axum_routing_htmx::HtmxResponse::new()
+            .location(three.htmx_location(id))
+            .push_url(three.htmx_push_url(id)),
+        format!("Redirecting to {id}"),
+    )
+}
+
+#[tokio::test]
+async fn test_typed_redirect() {
+    let router: axum::Router = axum::Router::new().htmx_route(redirect());
+    let server = TestServer::new(router).unwrap();
+
+    let response = server.post("/redirect/7").await;
+    response.assert_status_ok();
+    assert_eq!(response.header("hx-location"), "/three/7");
+    assert_eq!(response.header("hx-push-url"), "/three/7");
+
+    assert_eq!(
+        one().htmx_redirect(),
+        axum_routing_htmx::HxRedirect("/one".to_string())
+    );
+}
+
+#[hx_get("/encode/:name/*rest?q")]
+async fn encode(name: String, rest: String, q: String) -> String {
+    format!("{name}|{rest}|{q}")
+}
+
+#[tokio::test]
+async fn test_percent_encoding() {
+    let router: axum::Router = axum::Router::new().htmx_route(encode());
+    let server = TestServer::new(router).unwrap();
+
+    let path = encode().htmx_path("a b&c=d", "x/y z?", "1&x=2");
+    assert_eq!(path, "/encode/a%20b%26c%3Dd/x/y%20z%3F?q=1%26x%3D2");
+
+    let response = get_path(&server, &path).await;
+    response.assert_status_ok();
+    response.assert_text("a b&c=d|x/y z?|1&x=2");
+}
+
+#[hx_get("/files/:name/*rest")]
+async fn files(name: String, rest: String) {}
+
+#[test]
+fn test_dot_segments() {
+    let error = |name: &str, rest: &str| {
+        let mut path = String::new();
+        let url = files().htmx_url(name, rest);
+        url.write_to(&mut path).unwrap_err().to_string()
+    };
+    assert_eq!(
+        error("..", "a"),
+        "invalid path parameter `name`: dot segment `..`"
+    );
+    assert_eq!(
+        error("", "a"),
+        "invalid path parameter `name`: empty path segment"
+    );
+    assert_eq!(
+        error("a", "../../admin/delete"),
+        "invalid path parameter `rest`: dot segment `..`"
+    );
+    assert_eq!(
+        error("a", "b/./c"),
+        "invalid path parameter `rest`: dot segment `.`"
+    );
+    assert_eq!(
+        error("a", "b//c"),
+        "invalid path parameter `rest`: empty path segment"
+    );
+    assert_eq!(files().htmx_path("...", ".a/b."), "/files/.../.a/b.");
+}
+
+#[test]
+#[should_panic(expected = "invalid path parameter `name`: dot segment `..`")]
+fn test_dot_segment_panics() {
+    files().htmx_path("..", "../../admin/delete");
+}
+
+#[hx_get("/optional?offset&amount")]
+async fn optional_query(offset: Option<u32>, amount: u32) -> String {
+    format!("{offset:?} {amount}")
+}
+
+#[tokio::test]
+async fn test_optional_query() {
+    let router: axum::Router = axum::Router::new().htmx_route(optional_query());
+    let server = TestServer::new(router).unwrap();
+
+    let handler = optional_query();
+    assert_eq!(handler.htmx_path(None, 2), "/optional?amount=2");
+    assert_eq!(handler.htmx_path(Some(1), 2), "/optional?offset=1&amount=2");
+
+    for (path, expected) in [
+        (handler.htmx_path(None, 2), "None 2"),
+        (handler.htmx_path(Some(1), 2), "Some(1) 2"),
+    ] {
+        let response = get_path(&server, &path).await;
+        response.assert_status_ok();
+        response.assert_text(expected);
+    }
+}
+
+#[hx_get("/typed/:id/:name?tag")]
+async fn typed_path<
+    T: serde::de::DeserializeOwned + serde::Serialize + std::fmt::Display + Send + 'static,
+>(
+    id: T,
+    name: String,
+    tag: Option<u32>,
+) -> String {
+    format!("{id} {name}")
+}
+
+#[test]
+#[allow(clippy::needless_borrows_for_generic_args)]
+fn test_typed_htmx_path() {
+    let handler = typed_path::<u8>();
+    let name = String::from("John");
+    assert_eq!(handler.htmx_path(1, "John", None), "/typed/1/John");
+    assert_eq!(handler.htmx_path(&2, &name, Some(3)), "/typed/2/John?tag=3");
+    assert_eq!(handler.htmx_path(3, name, &Some(4)), "/typed/3/John?tag=4");
+}
+
+#[test]
+fn test_route_info() {
+    use axum_routing_htmx::{HtmxHandler, HtmxMethod, ParamInfo};
+
+    let info = generic_handler_with_complex_options::<u8>().route_info();
+    assert_eq!(info.method, HtmxMethod::Get);
+    assert_eq!(info.path, axum_path("/hello/:id", "/hello/{id}"));
+    assert_eq!(info.route, "/hello/:id?user_id&name");
+    assert_eq!(
+        info.path_params,
+        [ParamInfo {
+            name: "id",
+            ty: "u32"
+        }]
+    );
+    assert_eq!(
+        info.query_params,
+        [
+            ParamInfo {
+                name: "user_id",
+                ty: "String"
+            },
+            ParamInfo {
+                name: "name",
+                ty: "String"
+            },
+        ]
+    );
+    assert_eq!(info.state_type, "String");
+    assert_eq!(info.docs, "This is a handler that is documented!");
+
+    let info = typed_path::<u8>().route_info();
+    assert_eq!(info, typed_path::<u16>().route_info());
+    assert_eq!(info.path_params[0].ty, "T");
+    assert_eq!(info.query_params[0].ty, "Option<u32>");
+    assert_eq!(info.docs, "");
+}
+
+const ADMIN: HtmxPrefix = HtmxPrefix::new("/admin");
+
+#[tokio::test]
+async fn test_const_descriptor() {
+    const THREE: __HtmxHandler_three = three();
+    const ADMIN_THREE: __HtmxHandler_three = THREE.with_prefix(ADMIN);
+
+    let paths = (1..=3)
+        .map(|id| ADMIN_THREE.htmx_path(id))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        ["/admin/three/1", "/admin/three/2", "/admin/three/3"]
+    );
+
+    let router: axum::Router = axum::Router::new().htmx_route(THREE);
+    let server = TestServer::new(router).unwrap();
+    server
+        .get(&THREE.htmx_path(4))
+        .await
+        .assert_text("Hello 4!");
+}
+
+#[test]
+fn test_htmx_url() {
+    use std::fmt::Write;
+
+    use axum_routing_htmx::{HtmxAttrs, HtmxMethod};
+
+    let handler = three().with_prefix(ADMIN);
+    let mut html = String::new();
+    for id in 1..=2 {
+        write!(
+            html,
+            "<a {}></a>",
+            HtmxAttrs::new(handler.htmx_method(), handler.htmx_url(id))
+        )
+        .unwrap();
+    }
+    assert_eq!(
+        html,
+        r#"<a hx-get="/admin/three/1"></a><a hx-get="/admin/three/2"></a>"#
+    );
+
+    let url = encode().htmx_url("a b", "c/d", "e&f");
+    assert_eq!(url.to_string(), encode().htmx_path("a b", "c/d", "e&f"));
+
+    let mut path = String::new();
+    unsupported_query()
+        .htmx_url(vec![1, 2])
+        .write_to(&mut path)
+        .unwrap_err();
+}
+
+#[test]
+fn test_route_constants() {
+    use axum_routing_htmx::HtmxMethod;
+
+    const LINK: &str = __HtmxHandler_foo_bar::PATH;
+    assert_eq!(LINK, "/foo-bar");
+    assert_eq!(LINK, foo_bar().htmx_path());
+    assert_eq!(__HtmxHandler_foo_bar::AXUM_PATH, "/foo-bar");
+    assert_eq!(__HtmxHandler_two::METHOD, HtmxMethod::Post);
+
+    assert_eq!(
+        __HtmxHandler_three::AXUM_PATH,
+        axum_path("/three/:id", "/three/{id}")
+    );
+    assert_eq!(__HtmxHandler_three::METHOD, three().htmx_method());
+
+    let route = match "/foo-bar" {
+        __HtmxHandler_root::PATH => "root",
+        __HtmxHandler_foo_bar::PATH => "foo_bar",
+        _ => "other",
+    };
+    assert_eq!(route, "foo_bar");
+}
+
+#[tokio::test]
+async fn test_original_handler() {
+    assert_eq!(__HtmxHandler_three::handler(7).await, "Hello 7!");
+    assert_eq!(
+        __HtmxHandler_optional_query::handler(None, 2).await,
+        "None 2"
+    );
+    assert_eq!(
+        __HtmxHandler_typed_path::<u8>::handler(1, String::from("John"), None).await,
+        "1 John"
+    );
+    assert_eq!(
+        __HtmxHandler_generic_handler_with_complex_options::<u8>::handler(
+            1,
+            String::from("2"),
+            String::from("John"),
+            State(String::new()),
+            State(String::new()),
+            Json(3),
+        )
+        .await,
+        "Hello, 1 - 2 - John!"
+    );
+}
+
+#[hx_get("/sync/:id?name")]
+fn sync_handler(id: u32, name: Option<String>, State(greeting): State<String>) -> String {
+    format!("{greeting} {id} {name:?}")
+}
+
+#[tokio::test]
+async fn test_sync_handler() {
+    let router = axum::Router::new()
+        .htmx_route(sync_handler())
+        .with_state(String::from("Hello"));
+    let server = TestServer::new(router).unwrap();
+
+    let response = server.get(&sync_handler().htmx_path(1, None)).await;
+    response.assert_text("Hello 1 None");
+    assert_eq!(
+        __HtmxHandler_sync_handler::handler(2, None, State(String::from("Hi"))),
+        "Hi 2 None"
+    );
+}
+
+#[hx_get("/debug/:id?name", target = "#main", debug)]
+async fn debug_handler(id: u32, name: Option<String>, State(greeting): State<String>) -> String {
+    format!("{greeting} {id} {name:?}")
+}
+
+#[tokio::test]
+async fn test_debug_handler() {
+    let router = axum::Router::new()
+        .htmx_route(debug_handler())
+        .with_state(String::from("Hello"));
+    let server = TestServer::new(router).unwrap();
+
+    let response = server.get(&debug_handler().htmx_path(1, None)).await;
+    response.assert_text("Hello 1 None");
+}
+
+mod reexport {
+    pub use axum_routing_htmx as htmx;
+}
+
+#[hx_get("/reexported/:id?name", crate = crate::reexport::htmx)]
+async fn reexported(id: u32, name: Option<String>) -> String {
+    format!("{id} {name:?}")
+}
+
+#[tokio::test]
+async fn test_crate_override() {
+    let router = axum::Router::new().htmx_route(reexported());
+    let server = TestServer::new(router).unwrap();
+
+    let response = server.get(&reexported().htmx_path(1, None)).await;
+    response.assert_text("1 None");
+}
+
+#[hx_get("/rows/:page", htmx_only)]
+async fn rows(page: u32) -> String {
+    format!("Rows {page}")
+}
+
+#[hx_get("/sidebar", htmx_only = "/")]
+async fn sidebar() -> &'static str {
+    "Sidebar"
+}
+
+#[tokio::test]
+async fn test_htmx_only() {
+    use axum::http::HeaderValue;
+    use axum_routing_htmx::headers::HX_REQUEST;
+
+    let router = axum::Router::new().htmx_route(rows()).htmx_route(sidebar());
+    let server = TestServer::new(router).unwrap();
+
+    let response = server
+        .get(&rows().htmx_path(1))
+        .add_header(HX_REQUEST, HeaderValue::from_static("true"))
+        .await;
+    response.assert_text("Rows 1");
+    let response = server.get(&rows().htmx_path(1)).await;
+    response.assert_status_not_found();
+
+    let response = server
+        .get(&sidebar().htmx_path())
+        .add_header(HX_REQUEST, HeaderValue::from_static("true"))
+        .await;
+    response.assert_text("Sidebar");
+    let response = server.get(&sidebar().htmx_path()).await;
+    response.assert_status(axum::http::StatusCode::SEE_OTHER);
+    assert_eq!(response.header("location"), "/");
+}
+
+#[hx_get("/fragment/:id")]
+async fn fragment(id: u32) -> axum::response::Response {
+    use axum::{http::StatusCode, response::IntoResponse};
+
+    match id {
+        0 => StatusCode::NOT_FOUND.into_response(),
+        1 => StatusCode::NO_CONTENT.into_response(),
+        2 => Json([id]).into_response(),
+        id => axum::response::Html(format!("<p>{id}</p>")).into_response(),
+    }
+}
+
+#[tokio::test]
+async fn test_layout() {
+    use axum::http::HeaderValue;
+    use axum_routing_htmx::headers::{HX_BOOSTED, HX_HISTORY_RESTORE_REQUEST, HX_REQUEST};
+
+    let router: axum::Router = axum::Router::new().htmx_route_with_layout(fragment(), |body| {
+        axum::response::Html(format!("<main>{body}</main>"))
+    });
+    let server = TestServer::new(router).unwrap();
+    let path = fragment().htmx_path(3);
+
+    let response = server.get(&path).await;
+    response.assert_text("<main><p>3</p></main>");
+    assert_eq!(response.header("content-type"), "text/html; charset=utf-8");
+
+    let response = server
+        .get(&path)
+        .add_header(HX_REQUEST, HeaderValue::from_static("true"))
+        .await;
+    response.assert_text("<p>3</p>");
+
+    for header in [HX_BOOSTED, HX_HISTORY_RESTORE_REQUEST] {
+        let response = server
+            .get(&path)
+            .add_header(HX_REQUEST, HeaderValue::from_static("true"))
+            .add_header(header, HeaderValue::from_static("true"))
+            .await;
+        response.assert_text("<main><p>3</p></main>");
+    }
+
+    let response = server.get(&fragment().htmx_path(0)).await;
+    response.assert_status_not_found();
+    response.assert_text("");
+
+    let response = server.get(&fragment().htmx_path(1)).await;
+    response.assert_status(axum::http::StatusCode::NO_CONTENT);
+    response.assert_text("");
+
+    let response = server.get(&fragment().htmx_path(2)).await;
+    response.assert_text("[2]");
+    assert_eq!(response.header("content-type"), "application/json");
+}
+
+#[hx_get("/vary", cache_control = "no-cache")]
+async fn vary(
+    axum_routing_htmx::HxRequest(htmx): axum_routing_htmx::HxRequest,
+    axum_routing_htmx::HxTarget(target): axum_routing_htmx::HxTarget,
+    axum_routing_htmx::HxRequest(again): axum_routing_htmx::HxRequest,
+) -> &'static str {
+    if htmx {
+        "fragment"
+    } else {
+        "page"
+    }
+}
+
+#[hx_get("/vary-all")]
+async fn vary_all(htmx: axum_routing_htmx::HtmxRequest) {}
+
+#[hx_get("/cached", cache_control = "public")]
+async fn cached() -> ([(axum::http::HeaderName, &'static str); 2], &'static str) {
+    (
+        [
+            (axum::http::header::CACHE_CONTROL, "max-age=60"),
+            (axum::http::header::VARY, "hx-request"),
+        ],
+        "cached",
+    )
+}
+
+#[tokio::test]
+async fn test_caching_headers() {
+    let router: axum::Router = axum::Router::new()
+        .htmx_route(vary())
+        .htmx_route(cached())
+        .htmx_route(three())
+        .htmx_route(rows())
+        .htmx_route_with_layout(fragment(), |body| body);
+    let server = TestServer::new(router).unwrap();
+
+    let info = axum_routing_htmx::HtmxHandler::route_info(&vary());
+    assert_eq!(info.vary, ["HX-Request", "HX-Target"]);
+    assert_eq!(info.cache_control, Some("no-cache"));
+    assert_eq!(
+        axum_routing_htmx::HtmxHandler::route_info(&vary_all()).vary,
+        [
+            "HX-Request",
+            "HX-Boosted",
+            "HX-Current-URL",
+            "HX-History-Restore-Request",
+            "HX-Prompt",
+            "HX-Target",
+            "HX-Trigger",
+            "HX-Trigger-Name",
+        ]
+    );
+
+    let vary_of = |response: &TestResponse| {
+        response
+            .headers()
+            .get_all("vary")
+            .iter()
+            .map(|value| value.to_str().unwrap().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let response = server.get(&vary().htmx_path()).await;
+    assert_eq!(vary_of(&response), ["HX-Request", "HX-Target"]);
+    assert_eq!(response.header("cache-control"), "no-cache");
+
+    let response = server.get(&cached().htmx_path()).await;
+    assert_eq!(vary_of(&response), ["hx-request"]);
+    assert_eq!(response.header("cache-control"), "max-age=60");
+
+    let response = server.get(&three().htmx_path(1)).await;
+    assert!(vary_of(&response).is_empty());
+    assert!(response.maybe_header("cache-control").is_none());
+
+    let response = server.get(&rows().htmx_path(1)).await;
+    assert_eq!(vary_of(&response), ["HX-Request"]);
+
+    let response = server.get(&fragment().htmx_path(1)).await;
+    assert_eq!(
+        vary_of(&response),
+        ["HX-Request", "HX-Boosted", "HX-History-Restore-Request"]
+    );
+}
+
+#[tokio::test]
+async fn test_redirect_layer() {
+    use axum::{
+        http::{HeaderValue, StatusCode},
+        response::Redirect,
+        routing::get,
+    };
+    use axum_routing_htmx::{headers::HX_REQUEST, redirect::HtmxRedirectLayer};
+
+    let router = || {
+        axum::Router::new()
+            .route(
+                "/private",
+                get(|| async { ([("set-cookie", "session=")], Redirect::to("/login")) }),
+            )
+            .route("/public", get(|| async { "public" }))
+    };
+    let server = TestServer::new(router().layer(HtmxRedirectLayer::new())).unwrap();
+
+    let response = server.get("/private").await;
+    response.assert_status(StatusCode::SEE_OTHER);
+    assert_eq!(response.header("location"), "/login");
+    assert_eq!(response.header("vary"), "HX-Request");
+
+    let response = server
+        .get("/private")
+        .add_header(HX_REQUEST, HeaderValue::from_static("true"))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.header("hx-redirect"), "/login");
+    assert_eq!(response.header("vary"), "HX-Request");
+    assert_eq!(response.header("set-cookie"), "session=");
+    assert!(response.maybe_header("location").is_none());
+    response.assert_text("");
+
+    let response = server
+        .get("/public")
+        .add_header(HX_REQUEST, HeaderValue::from_static("true"))
+        .await;
+    response.assert_text("public");
+    assert!(response.maybe_header("vary").is_none());
+
+    let server = TestServer::new(router().layer(HtmxRedirectLayer::new().with_location())).unwrap();
+    let response = server
+        .get("/private")
+        .add_header(HX_REQUEST, HeaderValue::from_static("true"))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.header("hx-location"), "/login");
+}
+
+struct UsersController;
+
+#[axum_routing_htmx::hx_impl]
+impl UsersController {
+    /// Shows a user.
+    #[hx_get("/users/:id?tab")]
+    async fn show(id: u32, tab: Option<String>, State(greeting): State<String>) -> String {
+        format!("{greeting} {} {tab:?}", Self::name(id))
+    }
+
+    #[hx_post("/users/:id" with String)]
+    async fn update(id: u32) -> String {
+        format!("Updated {}", Self::name(id))
+    }
+
+    #[hx_get("/users" with String)]
+    fn list() -> &'static str {
+        "Users"
+    }
+
+    fn name(id: u32) -> String {
+        format!("user {id}")
+    }
+}
+
+#[tokio::test]
+async fn test_impl_handlers() {
+    let router = axum::Router::new()
+        .htmx_route(UsersController::show())
+        .htmx_route(UsersController::update())
+        .htmx_route(UsersController::list())
+        .with_state(String::from("Hello"));
+    let server = TestServer::new(router).unwrap();
+
+    let path = UsersController::show().htmx_path(1, Some("posts"));
+    assert_eq!(path, "/users/1?tab=posts");
+    let response = get_path(&server, &path).await;
+    response.assert_text(r#"Hello user 1 Some("posts")"#);
+    let response = server.post(&UsersController::update().htmx_path(2)).await;
+    response.assert_text("Updated user 2");
+    let response = server.get(&UsersController::list().htmx_path()).await;
+    response.assert_text("Users");
+
+    assert_eq!(
+        __HtmxHandler_UsersController_update::handler(3).await,
+        "Updated user 3"
+    );
+    assert_eq!(
+        axum_routing_htmx::HtmxHandler::route_info(&UsersController::show()).docs,
+        "Shows a user."
+    );
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+enum Order {
+    #[serde(rename = "asc")]
+    Ascending,
+    #[serde(rename = "desc")]
+    Descending,
+}
+
+#[hx_get("/serde?order&archived&search")]
+async fn serde_query(order: Order, archived: Option<bool>, search: Option<String>) -> String {
+    format!("{order:?} {archived:?} {search:?}")
+}
+
+#[tokio::test]
+async fn test_serde_query() {
+    let router: axum::Router = axum::Router::new().htmx_route(serde_query());
+    let server = TestServer::new(router).unwrap();
+
+    let handler = serde_query();
+    let search = Some(String::from("a b&c"));
+    assert_eq!(
+        handler.htmx_path(Order::Descending, Some(true), None),
+        "/serde?order=desc&archived=true"
+    );
+
+    for (path, expected) in [
+        (
+            handler.htmx_path(Order::Ascending, None, None),
+            "Ascending None None",
+        ),
+        (
+            handler.htmx_path(Order::Descending, Some(false), search.as_deref()),
+            "Descending Some(false) Some(\"a b&c\")",
+        ),
+    ] {
+        let response = get_path(&server, &path).await;
+        response.assert_status_ok();
+        response.assert_text(expected);
+    }
+}
+
+#[hx_get("/sorted/:order")]
+async fn sorted(order: Order) -> String {
+    format!("{order:?}")
+}
+
+#[tokio::test]
+async fn test_serde_path() {
+    let router: axum::Router = axum::Router::new().htmx_route(sorted());
+    let server = TestServer::new(router).unwrap();
+
+    let path = sorted().htmx_path(Order::Descending);
+    assert_eq!(path, "/sorted/desc");
+
+    let response = server.get(&path).await;
+    response.assert_status_ok();
+    response.assert_text("Descending");
+}
+
+#[hx_get("/unsupported?tags")]
+async fn unsupported_query(tags: Vec<u32>) {}
+
+#[test]
+#[should_panic(expected = "cannot serialize query parameter `tags`: unsupported value")]
+fn test_unsupported_query() {
+    unsupported_query().htmx_path(vec![1, 2]);
+}
+
+#[test]
+fn test_htmx_attrs() {
+    assert_eq!(three().htmx_attrs(1).to_string(), r#"hx-get="/three/1""#);
+    assert_eq!(
+        serde_query()
+            .htmx_attrs(Order::Ascending, None, Some("\"quoted\""))
+            .target("#list")
+            .swap("outerHTML")
+            .include("[name='csrf']")
+            .vals(serde_json::json!({ "page": 2 }))
+            .to_string(),
+        r##"hx-get="/serde?order=asc&amp;search=%22quoted%22" hx-target="#list" hx-swap="outerHTML" hx-include="[name=&#39;csrf&#39;]" hx-vals="{&quot;page&quot;:2}""##
+    );
+}
+
+#[hx_post(
+    "/todo/:id",
+    target = "#todo-list",
+    swap = "outerHTML swap:1s",
+    trigger = "click"
+)]
+async fn todo_with_defaults(id: u32) {}
+
+#[hx_get("/state-with-defaults" with String, target = "#main",)]
+async fn state_with_defaults() {}
+
+#[test]
+fn test_attribute_defaults() {
+    let handler = todo_with_defaults();
+    assert_eq!(
+        handler.htmx_attrs(1).to_string(),
+        r##"hx-post="/todo/1" hx-target="#todo-list" hx-swap="outerHTML swap:1s" hx-trigger="click""##
+    );
+    assert_eq!(
+        handler.htmx_attrs(2).target("this").to_string(),
+        r##"hx-post="/todo/2" hx-target="this" hx-swap="outerHTML swap:1s" hx-trigger="click""##
+    );
+    assert_eq!(
+        state_with_defaults().htmx_attrs().to_string(),
+        r##"hx-get="/state-with-defaults" hx-target="#main""##
+    );
+}
+
+#[hx_get("/braces/{id}/{*rest}")]
+async fn braces(id: u32, rest: String) -> String {
+    format!("{id} {rest}")
+}
+
+#[tokio::test]
+async fn test_brace_syntax() {
+    assert_eq!(
+        axum_routing_htmx::HtmxHandler::axum_router(braces()).0,
+        axum_path("/braces/:id/*rest", "/braces/{id}/{*rest}")
+    );
+
+    let router: axum::Router = axum::Router::new().htmx_route(braces());
+    let server = TestServer::new(router).unwrap();
+
+    let path = braces().htmx_path(1, "a/b");
+    assert_eq!(path, "/braces/1/a/b");
+    let response = server.get(&path).await;
+    response.assert_status_ok();
+    response.assert_text("1 a/b");
+}
+
+#[tokio::test]
+async fn test_nested_routes() {
+    let router: axum::Router = axum::Router::new().htmx_nest(
+        ADMIN,
+        axum::Router::new()
+            .htmx_route(three())
+            .htmx_route(optional_query()),
+    );
+    let server = TestServer::new(router).unwrap();
+
+    let handler = three().with_prefix(ADMIN);
+    assert_eq!(handler.prefix(), "/admin");
+    let path = handler.htmx_path(5);
+    assert_eq!(path, "/admin/three/5");
+    assert_eq!(
+        handler.htmx_attrs(5).to_string(),
+        r#"hx-get="/admin/three/5""#
+    );
+    let response = server.get(&path).await;
+    response.assert_status_ok();
+    response.assert_text("Hello 5!");
+
+    let path = optional_query().with_prefix(ADMIN).htmx_path(None, 1);
+    assert_eq!(path, "/admin/optional?amount=1");
+}
+
+#[test]
+#[should_panic(expected = "a prefix must not end with a `/`")]
+fn test_invalid_prefix() {
+    HtmxPrefix::new("/admin/");
+}
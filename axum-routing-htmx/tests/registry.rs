@@ -0,0 +1,83 @@
+#![allow(unused)]
+
+#[cfg(feature = "axum-08")]
+extern crate axum08 as axum;
+
+use axum::extract::State;
+use axum_routing_htmx::{hx_get, hx_post, registry, HtmxMethod, HtmxRouter};
+#[cfg(feature = "axum-07")]
+use axum_test::TestServer;
+#[cfg(feature = "axum-08")]
+use axum_test08::TestServer;
+
+#[hx_get("/item/:id")]
+async fn item(id: u32) -> String {
+    format!("Item {id}")
+}
+
+#[hx_post("/item/:id")]
+async fn update_item(id: u32) -> String {
+    format!("Updated {id}")
+}
+
+#[hx_get("/greeting")]
+async fn greeting(State(greeting): State<String>) -> String {
+    greeting
+}
+
+#[hx_get("/generic/:id")]
+async fn generic<
+    T: serde::de::DeserializeOwned + serde::Serialize + std::fmt::Display + Send + 'static,
+>(
+    id: T,
+) -> String {
+    id.to_string()
+}
+
+#[test]
+fn test_registered_handlers() {
+    let mut names = registry::handlers()
+        .map(|handler| handler.name)
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["greeting", "item", "update_item"]);
+
+    let item = registry::handlers()
+        .find(|handler| handler.name == "item")
+        .unwrap();
+    assert_eq!(item.info.method, HtmxMethod::Get);
+    assert_eq!(item.module_path, "registry");
+    assert_eq!(item.info.route, "/item/:id");
+    assert_eq!(item.info.state_type, "()");
+    assert!(item.has_state::<()>());
+    assert!(item.axum_router::<String>().is_none());
+
+    let mut names = registry::handlers_with_state::<String>().map(|handler| handler.name);
+    assert_eq!(names.next(), Some("greeting"));
+    assert_eq!(names.next(), None);
+}
+
+#[tokio::test]
+async fn test_routes_all() {
+    let router: axum::Router = axum::Router::new().htmx_routes_all();
+    let server = TestServer::new(router).unwrap();
+
+    server.get(&item().htmx_path(1)).await.assert_text("Item 1");
+    server
+        .post(&update_item().htmx_path(2))
+        .await
+        .assert_text("Updated 2");
+    server
+        .get(&greeting().htmx_path())
+        .await
+        .assert_status_not_found();
+
+    let router = axum::Router::new()
+        .htmx_routes_all()
+        .with_state(String::from("Hello!"));
+    let server = TestServer::new(router).unwrap();
+    server
+        .get(&greeting().htmx_path())
+        .await
+        .assert_text("Hello!");
+}